@@ -0,0 +1,132 @@
+/// Static class names added to elements, an alternative to styling with [Css](vertigo::Css)
+/// for apps having own stylesheet. Empty names are not added.
+///
+/// ```rust
+/// use vertigo_cmark::{CMarkClasses, CMarkConfig};
+///
+/// let config = CMarkConfig {
+///     classes: CMarkClasses {
+///         table: "table table-striped".to_string(),
+///         ..CMarkClasses::prefixed("md-")
+///     },
+///     ..Default::default()
+/// };
+/// assert_eq!(config.classes.h1, "md-h1");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CMarkClasses {
+    pub alert_caution: String,
+    pub alert_important: String,
+    pub alert_note: String,
+    pub alert_tip: String,
+    pub alert_warning: String,
+    pub blockquote: String,
+    pub br: String,
+    pub code: String,
+    pub codeblock: String,
+    pub codeblock_code: String,
+    pub code_figure: String,
+    pub code_line: String,
+    pub code_line_number: String,
+    pub code_title: String,
+    pub container: String,
+    pub dd: String,
+    pub del: String,
+    pub dl: String,
+    pub dt: String,
+    pub em: String,
+    pub footnote_backref: String,
+    pub footnote_definition: String,
+    pub footnote_label: String,
+    pub footnote_reference: String,
+    pub footnotes: String,
+    pub footnotes_list: String,
+    pub h1: String,
+    pub h2: String,
+    pub h3: String,
+    pub h4: String,
+    pub h5: String,
+    pub h6: String,
+    pub heading_anchor: String,
+    pub hr: String,
+    pub img: String,
+    pub input: String,
+    pub li: String,
+    pub a: String,
+    pub math_display: String,
+    pub math_inline: String,
+    pub ol: String,
+    pub p: String,
+    pub strong: String,
+    pub sub: String,
+    pub sup: String,
+    pub table: String,
+    pub tbody: String,
+    pub td: String,
+    pub th: String,
+    pub thead: String,
+    pub toc: String,
+    pub tr: String,
+    pub ul: String,
+}
+
+impl CMarkClasses {
+    /// Classes named after element kinds with a prefix, i.e. `md-h1`, `md-table`, `md-alert-note`.
+    pub fn prefixed(prefix: &str) -> Self {
+        Self {
+            alert_caution: format!("{}alert-caution", prefix),
+            alert_important: format!("{}alert-important", prefix),
+            alert_note: format!("{}alert-note", prefix),
+            alert_tip: format!("{}alert-tip", prefix),
+            alert_warning: format!("{}alert-warning", prefix),
+            blockquote: format!("{}blockquote", prefix),
+            br: format!("{}br", prefix),
+            code: format!("{}code", prefix),
+            codeblock: format!("{}codeblock", prefix),
+            codeblock_code: format!("{}codeblock-code", prefix),
+            code_figure: format!("{}code-figure", prefix),
+            code_line: format!("{}code-line", prefix),
+            code_line_number: format!("{}code-line-number", prefix),
+            code_title: format!("{}code-title", prefix),
+            container: format!("{}container", prefix),
+            dd: format!("{}dd", prefix),
+            del: format!("{}del", prefix),
+            dl: format!("{}dl", prefix),
+            dt: format!("{}dt", prefix),
+            em: format!("{}em", prefix),
+            footnote_backref: format!("{}footnote-backref", prefix),
+            footnote_definition: format!("{}footnote-definition", prefix),
+            footnote_label: format!("{}footnote-label", prefix),
+            footnote_reference: format!("{}footnote-reference", prefix),
+            footnotes: format!("{}footnotes", prefix),
+            footnotes_list: format!("{}footnotes-list", prefix),
+            h1: format!("{}h1", prefix),
+            h2: format!("{}h2", prefix),
+            h3: format!("{}h3", prefix),
+            h4: format!("{}h4", prefix),
+            h5: format!("{}h5", prefix),
+            h6: format!("{}h6", prefix),
+            heading_anchor: format!("{}heading-anchor", prefix),
+            hr: format!("{}hr", prefix),
+            img: format!("{}img", prefix),
+            input: format!("{}input", prefix),
+            li: format!("{}li", prefix),
+            a: format!("{}a", prefix),
+            math_display: format!("{}math-display", prefix),
+            math_inline: format!("{}math-inline", prefix),
+            ol: format!("{}ol", prefix),
+            p: format!("{}p", prefix),
+            strong: format!("{}strong", prefix),
+            sub: format!("{}sub", prefix),
+            sup: format!("{}sup", prefix),
+            table: format!("{}table", prefix),
+            tbody: format!("{}tbody", prefix),
+            td: format!("{}td", prefix),
+            th: format!("{}th", prefix),
+            thead: format!("{}thead", prefix),
+            toc: format!("{}toc", prefix),
+            tr: format!("{}tr", prefix),
+            ul: format!("{}ul", prefix),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::ops::RangeInclusive;
+use vertigo::DomNode;
+
+use crate::{
+    styling::{join_classes, styled},
+    tree::{Element, Node},
+    CMarkClasses, CMarkStyle,
+};
+
+/// Custom renderer of code blocks in given language, registered in [CMarkConfig](crate::CMarkConfig).
+///
+/// Returned node replaces the whole `<pre>` element. Closures can be used as renderers as well:
+///
+/// ```rust
+/// use vertigo::{DomElement, DomNode};
+/// use vertigo_cmark::{CMarkConfig, CodeInfo};
+///
+/// let config = CMarkConfig::default().with_code_renderer("mermaid", |_: &CodeInfo, source: &str| {
+///     DomNode::from(DomElement::new("div").attr("class", "mermaid").child_text(source))
+/// });
+/// ```
+pub trait CodeBlockRenderer {
+    fn render(&self, info: &CodeInfo, source: &str) -> DomNode;
+}
+
+impl<F> CodeBlockRenderer for F
+where
+    F: Fn(&CodeInfo, &str) -> DomNode,
+{
+    fn render(&self, info: &CodeInfo, source: &str) -> DomNode {
+        self(info, source)
+    }
+}
+
+/// Parsed info string of a fenced code block, i.e. `rust {1,3-5} linenos title="main.rs"`.
+///
+/// Recognized attributes:
+/// - first word is the language (unless it's a line range),
+/// - `{1,3-5}` - lines to highlight (numbered from 1),
+/// - `linenos` - show line numbers,
+/// - `title="main.rs"` - caption displayed above the code block.
+///
+/// All other `key=value` pairs and flags are kept in [attrs](CodeInfo::attrs).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CodeInfo {
+    /// Language of the code, empty if not provided
+    pub lang: String,
+    /// Ranges of lines to highlight
+    pub highlight_lines: Vec<RangeInclusive<usize>>,
+    /// Whether to render line numbers
+    pub line_numbers: bool,
+    /// Caption of the code block, usually a file name
+    pub title: Option<String>,
+    /// Other attributes (flags have empty value)
+    pub attrs: Vec<(String, String)>,
+}
+
+impl CodeInfo {
+    pub fn parse(info: &str) -> Self {
+        let mut code_info = Self::default();
+
+        for (idx, word) in split_words(info).into_iter().enumerate() {
+            if let Some(ranges) = word.strip_prefix('{').and_then(|w| w.strip_suffix('}')) {
+                code_info
+                    .highlight_lines
+                    .extend(ranges.split(',').filter_map(parse_range));
+            } else if let Some((key, value)) = word.split_once('=') {
+                let value = unquote(value).to_string();
+                match key {
+                    "title" => code_info.title = Some(value),
+                    _ => code_info.attrs.push((key.to_string(), value)),
+                }
+            } else if word == "linenos" {
+                code_info.line_numbers = true;
+            } else if idx == 0 {
+                code_info.lang = word.to_string();
+            } else {
+                code_info.attrs.push((word.to_string(), String::new()));
+            }
+        }
+
+        code_info
+    }
+
+    /// Whether the line (numbered from 1) should be highlighted
+    pub fn is_line_highlighted(&self, line: usize) -> bool {
+        self.highlight_lines
+            .iter()
+            .any(|range| range.contains(&line))
+    }
+
+    /// Whether lines need to be rendered separately
+    pub(crate) fn has_line_wrappers(&self) -> bool {
+        self.line_numbers || !self.highlight_lines.is_empty()
+    }
+}
+
+/// Splits by whitespace, but not inside quotes or braces
+fn split_words(info: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = None;
+    let mut closing = None;
+
+    for (idx, ch) in info.char_indices() {
+        match closing {
+            Some(closing_ch) if ch == closing_ch => closing = None,
+            Some(_) => {}
+            None if ch.is_whitespace() => {
+                if let Some(start) = start.take() {
+                    words.push(&info[start..idx]);
+                }
+                continue;
+            }
+            None => {
+                closing = match ch {
+                    '"' | '\'' => Some(ch),
+                    '{' => Some('}'),
+                    _ => None,
+                }
+            }
+        }
+        start.get_or_insert(idx);
+    }
+    if let Some(start) = start {
+        words.push(&info[start..]);
+    }
+
+    words
+}
+
+fn parse_range(range: &str) -> Option<RangeInclusive<usize>> {
+    let range = range.trim();
+    match range.split_once('-') {
+        Some((from, to)) => Some(from.trim().parse().ok()?..=to.trim().parse().ok()?),
+        None => {
+            let line = range.parse().ok()?;
+            Some(line..=line)
+        }
+    }
+}
+
+pub(crate) fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(unquoted) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return unquoted;
+        }
+    }
+    value
+}
+
+/// Splits source into lines (keeping newlines) or returns it as one line if `by_lines` is false.
+#[cfg(not(feature = "syntect"))]
+pub(crate) fn plain_lines(source: &str, by_lines: bool) -> Vec<Vec<Node>> {
+    if source.is_empty() {
+        return vec![];
+    }
+    if by_lines {
+        source
+            .split_inclusive('\n')
+            .map(|line| vec![Node::text(line)])
+            .collect()
+    } else {
+        vec![vec![Node::text(source)]]
+    }
+}
+
+/// Wraps every line into `<span class="line">`, with line number and highlighting
+pub(crate) fn wrap_lines(
+    info: &CodeInfo,
+    lines: Vec<Vec<Node>>,
+    styling: &CMarkStyle,
+    classes: &CMarkClasses,
+) -> Vec<Node> {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(idx, nodes)| {
+            let number = idx + 1;
+            let class = if info.is_line_highlighted(number) {
+                "line highlighted"
+            } else {
+                "line"
+            };
+            let mut element = styled(
+                Element::new("span"),
+                &styling.code_line,
+                &join_classes([class, &classes.code_line]),
+            );
+            if info.line_numbers {
+                element.add_child(styled(
+                    Element::new("span").child_text(number.to_string()),
+                    &styling.code_line_number,
+                    &join_classes(["line-number", &classes.code_line_number]),
+                ));
+            }
+            for node in nodes {
+                element.add_child(node);
+            }
+            element.into()
+        })
+        .collect()
+}
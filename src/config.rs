@@ -0,0 +1,105 @@
+#[cfg(feature = "syntect")]
+use std::sync::Arc;
+use std::{collections::HashMap, ops::Range, rc::Rc};
+
+use crate::classes::CMarkClasses;
+use crate::code::CodeBlockRenderer;
+#[cfg(feature = "syntect")]
+use crate::highlighting::{CodeTheme, HighlightMode, HighlighterContext};
+use crate::hooks::RenderHooks;
+use crate::html::HtmlPolicy;
+use crate::toc::TocConfig;
+
+/// Callback of clicked task checkbox, receiving source span of the list item and the new state.
+pub type TaskToggle = dyn Fn(Range<usize>, bool);
+
+/// Rendering settings other than [Options](crate::Options) of the parser and [styling](crate::CMarkStyle).
+#[derive(Clone, Default)]
+pub struct CMarkConfig {
+    /// Which raw HTML tags and attributes are rendered
+    pub html: HtmlPolicy,
+    /// Static classes of elements, applied along with [styling](crate::CMarkStyle)
+    pub classes: CMarkClasses,
+    /// Custom renderers of code blocks by language
+    pub code_renderers: HashMap<String, Rc<dyn CodeBlockRenderer>>,
+    /// Overrides of elements created by the writer
+    pub hooks: Option<Rc<dyn RenderHooks>>,
+    /// Whether headings get ids generated from their text (GitHub compatible),
+    /// ids set explicitly with `{#id}` are kept
+    pub heading_ids: bool,
+    /// Text of `a.heading-anchor` link appended to headings with id, i.e. `"#"`
+    pub heading_permalink: Option<String>,
+    /// Renders table of contents in place of `[TOC]` paragraph (headings get generated ids then)
+    pub toc: Option<TocConfig>,
+    /// Whether block-level elements get `data-source-start` and `data-source-end` attributes
+    /// with byte offsets of their markdown source, see [source_block_at](crate::source_block_at)
+    ///
+    /// NOTE: With this option [Markdown](crate::Markdown) renders again also blocks
+    /// which haven't changed but moved in the source.
+    pub source_positions: bool,
+    /// Called with source span of the list item and the new state when task checkbox is clicked,
+    /// checkboxes are enabled only if set, see [toggle_task](crate::toggle_task)
+    ///
+    /// NOTE: Like with [source_positions](CMarkConfig::source_positions), [Markdown](crate::Markdown)
+    /// renders again also blocks which haven't changed but moved in the source.
+    pub task_toggle: Option<Rc<TaskToggle>>,
+    /// Preloaded syntaxes and themes, if not provided the [shared](HighlighterContext::shared) one is used
+    #[cfg(feature = "syntect")]
+    pub highlighter: Option<Arc<HighlighterContext>>,
+    /// Theme for highlighted code blocks, presets of [styling](crate::CMarkStyle) have matching configs
+    /// (i.e. [CMarkConfig::github])
+    #[cfg(feature = "syntect")]
+    pub theme: CodeTheme,
+    /// Whether highlighted tokens are styled inline or with classes
+    #[cfg(feature = "syntect")]
+    pub highlight_mode: HighlightMode,
+}
+
+impl CMarkConfig {
+    /// Config for [CMarkStyle::github](crate::CMarkStyle::github) preset, with matching code theme.
+    pub fn github() -> Self {
+        Self::preset("InspiredGitHub")
+    }
+
+    /// Config for [CMarkStyle::github_dark](crate::CMarkStyle::github_dark) preset, with matching code theme.
+    pub fn github_dark() -> Self {
+        Self::preset("base16-ocean.dark")
+    }
+
+    /// Config for [CMarkStyle::minimal](crate::CMarkStyle::minimal) preset, with matching code theme.
+    pub fn minimal() -> Self {
+        Self::preset("InspiredGitHub")
+    }
+
+    #[cfg_attr(not(feature = "syntect"), allow(unused_variables))]
+    fn preset(code_theme: &str) -> Self {
+        Self {
+            #[cfg(feature = "syntect")]
+            theme: CodeTheme::named(code_theme),
+            ..Default::default()
+        }
+    }
+
+    /// Registers custom renderer for code blocks in given language.
+    pub fn with_code_renderer(
+        mut self,
+        lang: impl Into<String>,
+        renderer: impl CodeBlockRenderer + 'static,
+    ) -> Self {
+        self.code_renderers.insert(lang.into(), Rc::new(renderer));
+        self
+    }
+
+    /// Sets hooks overriding elements created by the writer.
+    pub fn with_hooks(mut self, hooks: impl RenderHooks + 'static) -> Self {
+        self.hooks = Some(Rc::new(hooks));
+        self
+    }
+
+    /// Enables task checkboxes, calling the function with source span of the list item
+    /// and the new state when one is clicked.
+    pub fn with_task_toggle(mut self, on_toggle: impl Fn(Range<usize>, bool) + 'static) -> Self {
+        self.task_toggle = Some(Rc::new(on_toggle));
+        self
+    }
+}
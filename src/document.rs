@@ -0,0 +1,222 @@
+use pulldown_cmark::{CowStr, Event, HeadingLevel, Parser, Tag};
+use std::{convert::TryFrom, ops::Range};
+use vertigo::DomNode;
+
+use crate::{generate, plain_text, CMarkConfig, CMarkStyle, Options};
+
+/// Owned tree of markdown document, which can be inspected and transformed before rendering.
+///
+/// ```rust
+/// use vertigo_cmark::{DemoteHeadings, Document, Options, RewriteLinks};
+///
+/// let mut document = Document::parse("# Title\n\n[Guide](guide.md)", Options::empty());
+/// document
+///     .apply(DemoteHeadings(1))
+///     .apply(RewriteLinks(|url: &str| url.replace(".md", ".html")));
+///
+/// assert_eq!(
+///     document.to_html_string(Default::default(), Default::default()),
+///     "<div><h2>Title</h2><p><a href=\"guide.html\">Guide</a></p></div>",
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Document {
+    pub nodes: Vec<DocumentNode>,
+}
+
+/// Node of [Document], with span of markdown source it was parsed from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DocumentNode {
+    /// Block (i.e. paragraph, list, table) or inline (i.e. emphasis, link) element with its content
+    Element {
+        tag: Tag<'static>,
+        children: Vec<DocumentNode>,
+        span: Range<usize>,
+    },
+    /// Content without children, i.e. text, code, rule or footnote reference
+    Leaf {
+        event: Event<'static>,
+        span: Range<usize>,
+    },
+}
+
+/// Transform pass over the [Document], i.e. rewriting links or adjusting headings.
+///
+/// Implemented for closures taking `&mut Document`.
+pub trait Transform {
+    fn transform(&self, document: &mut Document);
+}
+
+impl<F: Fn(&mut Document)> Transform for F {
+    fn transform(&self, document: &mut Document) {
+        self(document)
+    }
+}
+
+/// Rewrites destinations of links and sources of images with provided function.
+pub struct RewriteLinks<F>(pub F);
+
+impl<F: Fn(&str) -> String> Transform for RewriteLinks<F> {
+    fn transform(&self, document: &mut Document) {
+        document.visit_mut(&mut |node| {
+            if let DocumentNode::Element {
+                tag: Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. },
+                ..
+            } = node
+            {
+                *dest_url = CowStr::from(self.0(dest_url));
+            }
+        })
+    }
+}
+
+/// Lowers level of headings by provided number of levels (h1 becomes h2 and so on), up to h6.
+pub struct DemoteHeadings(pub usize);
+
+impl Transform for DemoteHeadings {
+    fn transform(&self, document: &mut Document) {
+        document.visit_mut(&mut |node| {
+            if let DocumentNode::Element {
+                tag: Tag::Heading { level, .. },
+                ..
+            } = node
+            {
+                let demoted = (*level as usize + self.0).min(6);
+                *level = HeadingLevel::try_from(demoted).unwrap_or(HeadingLevel::H6);
+            }
+        })
+    }
+}
+
+impl Document {
+    /// Parses a CommonMark string with provided [Options].
+    pub fn parse(text: &str, opts: Options) -> Self {
+        Self::from_events(Parser::new_ext(text, opts).into_offset_iter())
+    }
+
+    /// Builds the document from pulldown's events with their source spans.
+    pub fn from_events<'a>(iter: impl Iterator<Item = (Event<'a>, Range<usize>)>) -> Self {
+        // Stack of open elements, with their children collected so far
+        let mut stack: Vec<(Tag<'static>, Vec<DocumentNode>, Range<usize>)> = vec![];
+        let mut nodes = vec![];
+        for (event, span) in iter {
+            let node = match event {
+                Event::Start(tag) => {
+                    stack.push((tag.into_static(), vec![], span));
+                    continue;
+                }
+                Event::End(_) => match stack.pop() {
+                    Some((tag, children, span)) => DocumentNode::Element {
+                        tag,
+                        children,
+                        span,
+                    },
+                    None => continue,
+                },
+                event => DocumentNode::Leaf {
+                    event: event.into_static(),
+                    span,
+                },
+            };
+            match stack.last_mut() {
+                Some((_, children, _)) => children.push(node),
+                None => nodes.push(node),
+            }
+        }
+        // Unclosed elements (shouldn't happen)
+        while let Some((tag, children, span)) = stack.pop() {
+            let node = DocumentNode::Element {
+                tag,
+                children,
+                span,
+            };
+            match stack.last_mut() {
+                Some((_, children, _)) => children.push(node),
+                None => nodes.push(node),
+            }
+        }
+        Self { nodes }
+    }
+
+    /// Applies the [Transform] pass to the document.
+    pub fn apply(&mut self, transform: impl Transform) -> &mut Self {
+        transform.transform(self);
+        self
+    }
+
+    /// Calls the function for every node of the document, parents before their children.
+    pub fn visit_mut(&mut self, f: &mut impl FnMut(&mut DocumentNode)) {
+        for node in &mut self.nodes {
+            node.visit_mut(f);
+        }
+    }
+
+    /// Flattens the document back to pulldown's events.
+    pub fn events(&self) -> Vec<Event<'static>> {
+        self.events_with_spans()
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect()
+    }
+
+    /// Flattens the document back to pulldown's events with their source spans.
+    pub fn events_with_spans(&self) -> Vec<(Event<'static>, Range<usize>)> {
+        let mut events = vec![];
+        for node in &self.nodes {
+            node.write_events(&mut events);
+        }
+        events
+    }
+
+    /// Renders the document to Vertigo tree with provided [styling](CMarkStyle)
+    /// and [configuration](CMarkConfig).
+    pub fn to_vertigo(&self, style: CMarkStyle, config: CMarkConfig) -> DomNode {
+        generate::generate_tree(self.events_with_spans().into_iter(), style, config)
+    }
+
+    /// Renders the document to HTML string, see [to_html_string](crate::to_html_string).
+    pub fn to_html_string(&self, style: CMarkStyle, config: CMarkConfig) -> String {
+        generate::generate_html(self.events_with_spans().into_iter(), style, config)
+    }
+
+    /// Renders the document to plain text, see [to_plain_text](crate::to_plain_text).
+    pub fn to_plain_text(&self, limit: Option<usize>) -> String {
+        plain_text::generate_plain_text(self.events().into_iter(), limit)
+    }
+}
+
+impl DocumentNode {
+    /// Span of markdown source the node was parsed from.
+    pub fn span(&self) -> &Range<usize> {
+        match self {
+            Self::Element { span, .. } | Self::Leaf { span, .. } => span,
+        }
+    }
+
+    /// Calls the function for the node and its descendants, parents before their children.
+    pub fn visit_mut(&mut self, f: &mut impl FnMut(&mut DocumentNode)) {
+        f(self);
+        if let Self::Element { children, .. } = self {
+            for child in children {
+                child.visit_mut(f);
+            }
+        }
+    }
+
+    fn write_events(&self, events: &mut Vec<(Event<'static>, Range<usize>)>) {
+        match self {
+            Self::Element {
+                tag,
+                children,
+                span,
+            } => {
+                events.push((Event::Start(tag.clone()), span.clone()));
+                for child in children {
+                    child.write_events(events);
+                }
+                events.push((Event::End(tag.to_end()), span.clone()));
+            }
+            Self::Leaf { event, span } => events.push((event.clone(), span.clone())),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use pulldown_cmark::MetadataBlockKind;
+#[cfg(feature = "front-matter")]
+use std::collections::BTreeMap;
+
+#[cfg(feature = "front-matter")]
+use crate::code::unquote;
+
+/// Metadata block of the document (`---` YAML or `+++` TOML), as written in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrontMatter {
+    pub kind: MetadataBlockKind,
+    /// Content of the block without delimiters
+    pub raw: String,
+}
+
+/// Value from [front matter map](FrontMatter::to_map).
+#[cfg(feature = "front-matter")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetaValue {
+    /// String, number, boolean or date as written (strings unquoted)
+    Text(String),
+    /// List of scalar values
+    List(Vec<String>),
+}
+
+#[cfg(feature = "front-matter")]
+impl FrontMatter {
+    /// Parses top-level `key: value` (YAML) or `key = value` (TOML) pairs.
+    ///
+    /// Only flat metadata is supported: scalar values and lists of scalars
+    /// (inline `[a, b]` or YAML `- item` lines). Nested maps and TOML tables are skipped.
+    ///
+    /// ```rust
+    /// use vertigo_cmark::{FrontMatter, MetadataBlockKind, MetaValue};
+    ///
+    /// let front_matter = FrontMatter {
+    ///     kind: MetadataBlockKind::PlusesStyle,
+    ///     raw: "title = \"Hello\"\ntags = [\"rust\", \"web\"]\n".to_string(),
+    /// };
+    /// let map = front_matter.to_map();
+    /// assert_eq!(map["title"], MetaValue::Text("Hello".to_string()));
+    /// ```
+    pub fn to_map(&self) -> BTreeMap<String, MetaValue> {
+        let separator = match self.kind {
+            MetadataBlockKind::YamlStyle => ':',
+            MetadataBlockKind::PlusesStyle => '=',
+        };
+
+        let mut map = BTreeMap::new();
+        // Key of YAML list written in following lines
+        let mut list_key: Option<String> = None;
+
+        for line in self.raw.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            if line.starts_with(char::is_whitespace) || trimmed.starts_with("- ") {
+                if let Some(key) = &list_key {
+                    match trimmed.strip_prefix("- ") {
+                        Some(item) => {
+                            if let Some(MetaValue::List(items)) = map.get_mut(key) {
+                                items.push(unquote(item.trim()).to_string());
+                            }
+                        }
+                        // Nested map
+                        None => {
+                            map.remove(key);
+                            list_key = None;
+                        }
+                    }
+                }
+                continue;
+            }
+            list_key = None;
+            // TOML table ends top-level pairs
+            if separator == '=' && trimmed.starts_with('[') {
+                break;
+            }
+
+            let (key, value) = match trimmed.split_once(separator) {
+                Some((key, value)) => (unquote(key.trim()), value.trim()),
+                None => continue,
+            };
+            let value = if value.is_empty() {
+                if separator == ':' {
+                    list_key = Some(key.to_string());
+                }
+                MetaValue::List(vec![])
+            } else if let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                MetaValue::List(
+                    items
+                        .split(',')
+                        .map(|item| unquote(item.trim()).to_string())
+                        .filter(|item| !item.is_empty())
+                        .collect(),
+                )
+            } else {
+                MetaValue::Text(unquote(value).to_string())
+            };
+            map.insert(key.to_string(), value);
+        }
+
+        map
+    }
+}
@@ -4,15 +4,29 @@ use pulldown_cmark::{
     Alignment, BlockQuoteKind, CodeBlockKind, CowStr, Event, Event::*, HeadingLevel, LinkType, Tag,
     TagEnd,
 };
+#[cfg(feature = "syntect")]
+use std::sync::Arc;
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Range,
     rc::Rc,
 };
-use vertigo::{log, Css, DomElement, DomNode, DomText};
+use vertigo::{log, Css, DomNode};
 
+#[cfg(not(feature = "syntect"))]
+use crate::code::plain_lines;
 #[cfg(feature = "syntect")]
-use crate::highlighting::highlight;
-use crate::styling::CMarkStyle;
+use crate::highlighting::{highlight, HighlighterContext};
+use crate::{
+    code::{wrap_lines, CodeInfo},
+    config::CMarkConfig,
+    front_matter::FrontMatter,
+    hooks::ElementTag,
+    html::{is_raw_text_element, is_void_element, tokenize, HtmlToken},
+    styling::{at_depth, join_classes, styled, CMarkStyle},
+    toc::{build_toc, is_toc_placeholder, toc_list, Heading, Headings},
+    tree::{Element, Node},
+};
 
 enum TableState {
     Head,
@@ -20,141 +34,287 @@ enum TableState {
 }
 
 struct VertigoWriter<'a, I> {
-    /// Iterator supplying events.
+    /// Iterator supplying events with their source spans.
     iter: I,
+    /// Span of markdown source of the current event
+    span: Range<usize>,
+    /// Spans of list items being written, passed to the callback of task checkboxes
+    item_spans: Vec<Range<usize>>,
 
     /// Whether if inside a metadata block (text should not be written)
     in_non_writing_block: bool,
+    /// First metadata block of the document
+    front_matter: Option<FrontMatter>,
+    /// Whether text of metadata block is collected into front matter
+    in_front_matter: bool,
 
     table_state: TableState,
     table_alignments: Vec<Alignment>,
     table_cell_index: usize,
+    /// Numbers of defined footnotes, references to other names are rendered as text
     numbers: HashMap<CowStr<'a>, usize>,
+    /// Number of references to each footnote written so far
+    ref_counts: HashMap<CowStr<'a>, usize>,
+    /// Name of footnote definition being written
+    in_footnote: Option<CowStr<'a>>,
+    /// Written footnote definitions, rendered in section at the end
+    footnotes: Vec<(usize, CowStr<'a>, Node)>,
 
     // Stack of nested nodes
-    soc: VecDeque<DomNode>,
+    soc: VecDeque<Node>,
+    /// Top-level nodes, popped from the stack without parent
+    roots: Vec<Node>,
+
+    /// Nesting level of markdown tags, used to keep HTML elements within tags they were opened in
+    tag_level: usize,
+    /// HTML elements currently open on stack, with tag level they were opened at
+    html_stack: Vec<(&'static str, usize)>,
+    /// Name of dropped raw text element (i.e. `<script>`) which content is being skipped,
+    /// with tag level it was opened at
+    html_skip_until: Option<(String, usize)>,
+    /// Number of inline markdown tags opened inside skipped raw text element
+    html_skipped_tags: usize,
 
     styling: Rc<CMarkStyle>,
+    config: Rc<CMarkConfig>,
 
-    #[cfg(feature = "syntect")]
-    in_code_block: Option<CowStr<'a>>,
+    /// Code block being written, its content is rendered at the end of block
+    in_code_block: Option<(CodeInfo, String)>,
+    /// Nesting depth of lists and blockquotes being written
+    list_depth: usize,
+    blockquote_depth: usize,
+
+    /// Headings of the document, with ids
+    headings: Headings,
+    /// Heading which has just ended
+    ended_heading: Option<Heading>,
+    /// Ids of headings generated upfront, used instead of ones generated from text
+    heading_ids: VecDeque<String>,
+
+    /// Text of paragraph which may be a `[TOC]` placeholder
+    toc_placeholder: Option<String>,
+    /// Headings of the whole document for table of contents
+    toc: Vec<Heading>,
 }
 
 impl<'a, I> VertigoWriter<'a, I>
 where
-    I: Iterator<Item = Event<'a>>,
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
 {
-    fn new(iter: I, styling: CMarkStyle) -> Self {
+    fn new(iter: I, styling: Rc<CMarkStyle>, config: Rc<CMarkConfig>) -> Self {
         Self {
             iter,
+            span: 0..0,
+            item_spans: vec![],
             in_non_writing_block: false,
+            front_matter: None,
+            in_front_matter: false,
             table_state: TableState::Head,
             table_alignments: vec![],
             table_cell_index: 0,
             numbers: HashMap::new(),
+            ref_counts: HashMap::new(),
+            in_footnote: None,
+            footnotes: vec![],
             soc: VecDeque::new(),
-            styling: Rc::new(styling),
-            #[cfg(feature = "syntect")]
+            roots: vec![],
+            tag_level: 0,
+            html_stack: vec![],
+            html_skip_until: None,
+            html_skipped_tags: 0,
+            styling,
+            config,
             in_code_block: None,
+            list_depth: 0,
+            blockquote_depth: 0,
+            headings: Headings::default(),
+            ended_heading: None,
+            heading_ids: VecDeque::new(),
+            toc_placeholder: None,
+            toc: vec![],
         }
     }
 
-    fn run(mut self) -> DomNode {
-        self.push_element_styled(DomElement::new("div"), &self.styling.clone().container);
-        while let Some(event) = self.iter.next() {
+    fn run(self) -> Node {
+        self.run_with_front_matter().0
+    }
+
+    fn run_with_front_matter(mut self) -> (Node, Option<FrontMatter>) {
+        self.push_element_styled(
+            Element::new("div"),
+            &self.styling.clone().container,
+            &self.config.clone().classes.container,
+        );
+        self.write_events();
+        self.write_footnotes();
+        self.pop_node();
+        let root = self.roots.pop().unwrap_or_else(|| {
+            log::error!("Popping nesting did not produce root node!");
+            Element::new("div").into()
+        });
+        (root, self.front_matter)
+    }
+
+    /// Writes events without the container, returning top-level nodes
+    fn run_fragment(mut self) -> Vec<Node> {
+        self.write_events();
+        self.write_footnotes();
+        self.roots
+    }
+
+    fn write_events(&mut self) {
+        while let Some((event, span)) = self.iter.next() {
+            self.span = span;
+            if let Some(heading) = self.headings.event(&event) {
+                self.ended_heading = Some(heading);
+            }
+            if let Some(placeholder) = &mut self.toc_placeholder {
+                match &event {
+                    Text(text) => placeholder.push_str(text),
+                    End(TagEnd::Paragraph) => {}
+                    _ => self.toc_placeholder = None,
+                }
+            }
+            if self.skip_raw_text(&event) {
+                continue;
+            }
             match event {
                 Start(tag) => {
+                    let depth = self.soc.len();
+                    let block = self.config.source_positions && is_block(&tag);
                     self.start_tag(tag);
+                    if block {
+                        self.mark_source(depth);
+                    }
                 }
                 End(tag) => {
                     self.end_tag(tag);
                 }
                 Text(text) => {
                     if !self.in_non_writing_block {
-                        #[cfg(feature = "syntect")]
-                        if let Some(ref info) = self.in_code_block {
-                            for el in highlight(info, &text) {
-                                self.add_child(el)
-                            }
+                        if let Some((_, source)) = &mut self.in_code_block {
+                            source.push_str(&text);
                         } else {
-                            self.add_child(DomText::new(text));
+                            self.add_child(Node::text(text));
+                        }
+                    } else if self.in_front_matter {
+                        if let Some(front_matter) = &mut self.front_matter {
+                            front_matter.raw.push_str(&text);
                         }
-                        #[cfg(not(feature = "syntect"))]
-                        self.add_child(DomText::new(text));
                     }
                 }
                 Code(text) => {
-                    let element = DomElement::new("code").child(DomText::new(text));
-                    self.add_child(element);
+                    let element = Element::new("code").child(Node::text(text));
+                    self.add_element_styled(
+                        element,
+                        &self.styling.clone().code,
+                        &self.config.clone().classes.code,
+                    );
                 }
                 InlineMath(text) => {
-                    let element = DomElement::new("span")
-                        .attr("class", "math math-inline")
-                        .child(DomText::new(text));
-                    self.add_child(element);
+                    let element = Element::new("span").child(Node::text(text));
+                    self.add_element_styled(
+                        element,
+                        &self.styling.clone().math_inline,
+                        &join_classes(["math math-inline", &self.config.classes.math_inline]),
+                    );
                 }
                 DisplayMath(text) => {
-                    let element = DomElement::new("span")
-                        .attr("class", "math math-display")
-                        .child(DomText::new(text));
-                    self.add_child(element);
+                    let element = Element::new("span").child(Node::text(text));
+                    self.add_element_styled(
+                        element,
+                        &self.styling.clone().math_display,
+                        &join_classes(["math math-display", &self.config.classes.math_display]),
+                    );
                 }
-                Html(_html) | InlineHtml(_html) => {
-                    // TODO:
+                Html(html) => {
+                    if !self.in_non_writing_block {
+                        self.write_html(&html, true);
+                    }
+                }
+                InlineHtml(html) => {
+                    if !self.in_non_writing_block {
+                        self.write_html(&html, false);
+                    }
                 }
                 SoftBreak => {
                     // Add space to not glue sibling texts in render
-                    self.add_child(DomText::new(" "));
+                    self.add_child(Node::text(" "));
                 }
                 HardBreak => {
-                    self.add_child_name("br");
+                    self.add_element_styled(
+                        Element::new("br"),
+                        &self.styling.clone().br,
+                        &self.config.clone().classes.br,
+                    );
                 }
                 Rule => {
-                    self.add_child_name("hr");
-                }
-                FootnoteReference(name) => {
-                    let len = self.numbers.len() + 1;
-                    let link = DomElement::new("a").attr("href", ["#", name.as_ref()].concat());
-                    let number = *self.numbers.entry(name).or_insert(len);
-                    link.add_child_text(number.to_string());
-                    let mut element = DomElement::new("sup")
-                        .attr("class", "footnote-reference")
-                        .child(link);
-                    if !self.styling.sup.groups.is_empty() {
-                        element = element.css(&self.styling.sup);
+                    let mut element = Element::new("hr");
+                    if self.config.source_positions {
+                        add_source_attrs(&mut element, &self.span);
                     }
-                    self.add_child(element);
+                    self.add_element_styled(
+                        element,
+                        &self.styling.clone().hr,
+                        &self.config.clone().classes.hr,
+                    );
                 }
-                TaskListMarker(true) => {
-                    self.add_child(
-                        DomElement::new("input")
-                            .attr("disabled", "")
-                            .attr("type", "checkbox")
-                            .attr("checked", "checked"),
+                FootnoteReference(name) => {
+                    let number = match self.numbers.get(&name) {
+                        Some(number) => *number,
+                        None => {
+                            // Undefined footnote
+                            self.add_child(Node::text(format!("[^{}]", name)));
+                            continue;
+                        }
+                    };
+                    let count = self.ref_counts.entry(name.clone()).or_default();
+                    *count += 1;
+                    let link = styled(
+                        Element::new("a")
+                            .attr("href", format!("#fn-{}", name))
+                            .attr("id", footnote_ref_id(&name, *count))
+                            .child_text(number.to_string()),
+                        &self.styling.footnote_label,
+                        &self.config.classes.footnote_label,
+                    );
+                    let element = styled(
+                        Element::new("sup").child(link),
+                        &self.styling.footnote_reference,
+                        &join_classes([
+                            "footnote-reference",
+                            &self.config.classes.footnote_reference,
+                        ]),
                     );
+                    self.add_child(element);
                 }
-                TaskListMarker(false) => {
-                    self.add_child(
-                        DomElement::new("input")
-                            .attr("disabled", "")
-                            .attr("type", "checkbox"),
+                TaskListMarker(checked) => {
+                    let element = self.task_checkbox(checked);
+                    self.add_element_styled(
+                        element,
+                        &self.styling.clone().input,
+                        &self.config.clone().classes.input,
                     );
                 }
             }
         }
-        self.pop_node().unwrap_or_else(|| {
-            log::error!("Popping nesting did not produce root node!");
-            DomElement::new("div").into()
-        })
+        self.close_html(0);
     }
 
     /// Pushes dom element on stack
     fn start_tag(&mut self, tag: Tag<'a>) {
         let styling = self.styling.clone();
+        let config = self.config.clone();
+        if !matches!(tag, Tag::HtmlBlock | Tag::Image { .. }) {
+            self.tag_level += 1;
+        }
         match &tag {
             Tag::HtmlBlock => {}
             Tag::Paragraph => {
-                self.push_elname("p", &styling.p);
+                if self.config.toc.is_some() {
+                    self.toc_placeholder = Some(String::new());
+                }
+                self.push_hooked(ElementTag::Paragraph, "p", &styling.p, &config.classes.p);
             }
             Tag::Heading {
                 level,
@@ -162,119 +322,274 @@ where
                 classes,
                 attrs: _, // Vertigo doesn't support dynamic attributes keys
             } => {
-                let (el_name, css) = match level {
-                    HeadingLevel::H1 => ("h1", &styling.h1),
-                    HeadingLevel::H2 => ("h2", &styling.h2),
-                    HeadingLevel::H3 => ("h3", &styling.h3),
-                    HeadingLevel::H4 => ("h4", &styling.h4),
-                    HeadingLevel::H5 => ("h5", &styling.h5),
-                    HeadingLevel::H6 => ("h6", &styling.h6),
+                if let Some(element) = self.hooked(ElementTag::Heading {
+                    level: *level,
+                    id: id.as_deref(),
+                    classes: classes.iter().map(|class| class.as_ref()).collect(),
+                }) {
+                    self.push_node(element);
+                    return;
+                }
+                let (el_name, css, class) = match level {
+                    HeadingLevel::H1 => ("h1", &styling.h1, &config.classes.h1),
+                    HeadingLevel::H2 => ("h2", &styling.h2, &config.classes.h2),
+                    HeadingLevel::H3 => ("h3", &styling.h3, &config.classes.h3),
+                    HeadingLevel::H4 => ("h4", &styling.h4, &config.classes.h4),
+                    HeadingLevel::H5 => ("h5", &styling.h5, &config.classes.h5),
+                    HeadingLevel::H6 => ("h6", &styling.h6, &config.classes.h6),
                 };
-                let element = DomElement::new(el_name);
+                let mut element = Element::new(el_name);
                 if let Some(id) = id {
                     element.add_attr("id", id);
                 }
-                if !classes.is_empty() {
-                    let value = classes.join(" ");
-                    element.add_attr("class", value);
-                }
-                self.push_element_styled(element, css);
+                let class = join_classes(
+                    classes
+                        .iter()
+                        .map(|class| class.as_ref())
+                        .chain([class.as_str()]),
+                );
+                self.push_element_styled(element, css, &class);
             }
             Tag::Table(alignments) => {
                 self.table_alignments = alignments.clone();
-                self.push_element_styled(DomElement::new("table"), &styling.table)
+                self.push_hooked(
+                    ElementTag::Table { alignments },
+                    "table",
+                    &styling.table,
+                    &config.classes.table,
+                );
             }
             Tag::TableHead => {
                 self.table_state = TableState::Head;
                 self.table_cell_index = 0;
-                self.push_elname("thead", &styling.thead);
-                self.push_elname("tr", &styling.tr);
+                self.push_hooked(
+                    ElementTag::TableHead,
+                    "thead",
+                    &styling.thead,
+                    &config.classes.thead,
+                );
+                self.push_hooked(ElementTag::TableRow, "tr", &styling.tr, &config.classes.tr);
             }
             Tag::TableRow => {
                 self.table_cell_index = 0;
-                self.push_elname("tr", &styling.tr);
+                self.push_hooked(ElementTag::TableRow, "tr", &styling.tr, &config.classes.tr);
             }
             Tag::TableCell => {
-                let (el_name, style) = match self.table_state {
-                    TableState::Head => ("th", &styling.th),
-                    TableState::Body => ("td", &styling.td),
+                let (el_name, style, class) = match self.table_state {
+                    TableState::Head => ("th", &styling.th, &config.classes.th),
+                    TableState::Body => ("td", &styling.td, &config.classes.td),
                 };
-                let element = DomElement::new(el_name);
+                let alignment = self
+                    .table_alignments
+                    .get(self.table_cell_index)
+                    .copied()
+                    .unwrap_or(Alignment::None);
+                if let Some(element) = self.hooked(ElementTag::TableCell {
+                    head: matches!(self.table_state, TableState::Head),
+                    alignment,
+                }) {
+                    self.push_node(element);
+                    return;
+                }
+                let mut element = Element::new(el_name);
                 match self.table_alignments.get(self.table_cell_index) {
                     Some(&Alignment::Left) => element.add_attr("style", "text-align: left"),
                     Some(&Alignment::Center) => element.add_attr("style", "text-align: center"),
                     Some(&Alignment::Right) => element.add_attr("style", "text-align: right"),
                     _ => (),
                 }
-                self.push_element_styled(element, style);
+                self.push_element_styled(element, style, class);
             }
-            #[cfg(feature = "syntect")]
             Tag::CodeBlock(info) => {
-                if let CodeBlockKind::Fenced(info) = info {
-                    self.push_element_styled(DomElement::new("pre"), &styling.codeblock);
-                    // TODO: info
-                    self.in_code_block = Some(info.clone());
+                let info = match info {
+                    CodeBlockKind::Fenced(info) => CodeInfo::parse(info),
+                    CodeBlockKind::Indented => CodeInfo::default(),
+                };
+
+                // Custom renderer produces whole element at the end of block
+                if self.config.code_renderers.contains_key(&info.lang) {
+                    self.in_code_block = Some((info, String::new()));
+                    return;
                 }
-            }
-            #[cfg(not(feature = "syntect"))]
-            Tag::CodeBlock(info) => {
-                self.push_element_styled(DomElement::new("pre"), &styling.codeblock);
-                let element = DomElement::new("code");
-                match info {
-                    CodeBlockKind::Fenced(info) => {
-                        let lang = info.split(' ').next().unwrap_or_default();
-                        if !lang.is_empty() {
-                            element.add_attr("class", format!("language-{lang}"));
-                        }
-                    }
-                    CodeBlockKind::Indented => {}
+
+                if let Some(title) = &info.title {
+                    let caption = styled(
+                        Element::new("figcaption").child_text(title),
+                        &styling.code_title,
+                        &join_classes(["code-title", &config.classes.code_title]),
+                    );
+                    self.push_element_styled(
+                        Element::new("figure").child(caption),
+                        &styling.code_figure,
+                        &join_classes(["code-block", &config.classes.code_figure]),
+                    );
+                }
+                self.push_code_block_pre();
+
+                let language = if info.lang.is_empty() {
+                    String::new()
+                } else {
+                    format!("language-{}", info.lang)
                 };
-                self.push_node(element);
+                self.push_element_styled(
+                    Element::new("code"),
+                    &styling.codeblock_code,
+                    &join_classes([language.as_str(), &config.classes.codeblock_code]),
+                );
+                self.in_code_block = Some((info, String::new()));
             }
             Tag::BlockQuote(kind) => {
-                let element = DomElement::new("blockquote");
+                self.blockquote_depth += 1;
+                if let Some(element) = self.hooked(ElementTag::BlockQuote { kind: *kind }) {
+                    self.push_node(element);
+                    return;
+                }
+                let element = Element::new("blockquote");
+                let mut css = at_depth(
+                    &styling.blockquote,
+                    &styling.blockquote_depths,
+                    self.blockquote_depth,
+                );
+                let mut class = config.classes.blockquote.clone();
 
                 if let Some(kind) = kind {
-                    let kind_value = match kind {
-                        BlockQuoteKind::Note => "markdown-alert-note",
-                        BlockQuoteKind::Tip => "markdown-alert-tip",
-                        BlockQuoteKind::Important => "markdown-alert-important",
-                        BlockQuoteKind::Warning => "markdown-alert-warning",
-                        BlockQuoteKind::Caution => "markdown-alert-caution",
+                    let (kind_value, kind_css, kind_class) = match kind {
+                        BlockQuoteKind::Note => (
+                            "markdown-alert-note",
+                            &styling.alert_note,
+                            &config.classes.alert_note,
+                        ),
+                        BlockQuoteKind::Tip => (
+                            "markdown-alert-tip",
+                            &styling.alert_tip,
+                            &config.classes.alert_tip,
+                        ),
+                        BlockQuoteKind::Important => (
+                            "markdown-alert-important",
+                            &styling.alert_important,
+                            &config.classes.alert_important,
+                        ),
+                        BlockQuoteKind::Warning => (
+                            "markdown-alert-warning",
+                            &styling.alert_warning,
+                            &config.classes.alert_warning,
+                        ),
+                        BlockQuoteKind::Caution => (
+                            "markdown-alert-caution",
+                            &styling.alert_caution,
+                            &config.classes.alert_caution,
+                        ),
                     };
-                    element.add_attr("class", kind_value);
+                    class = join_classes([kind_value, &class, kind_class]);
+                    css = css.extend(kind_css.clone());
                 };
-                self.push_element_styled(element, &styling.blockquote);
-            }
-            Tag::List(Some(1)) => self.push_elname("ol", &styling.ol),
-            Tag::List(Some(start)) => {
-                self.push_element_styled(DomElement::new("ol").attr("start", start), &styling.ol);
-            }
-            Tag::List(None) => self.push_elname("ul", &styling.ul),
-            Tag::Item => self.push_elname("li", &styling.li),
-            Tag::DefinitionList => self.push_elname("dl", &styling.dl),
-            Tag::DefinitionListTitle => self.push_elname("dt", &styling.dt),
-            Tag::DefinitionListDefinition => self.push_elname("dd", &styling.dd),
-            Tag::Subscript => self.push_elname("sub", &styling.sub),
-            Tag::Superscript => self.push_elname("sup", &styling.sup),
-            Tag::Emphasis => self.push_elname("em", &styling.em),
-            Tag::Strong => self.push_elname("strong", &styling.strong),
-            Tag::Strikethrough => self.push_elname("del", &styling.del),
+                self.push_element_styled(element, &css, &class);
+            }
+            Tag::List(start) => {
+                self.list_depth += 1;
+                if let Some(element) = self.hooked(ElementTag::List { start: *start }) {
+                    self.push_node(element);
+                    return;
+                }
+                let ol_css = at_depth(&styling.ol, &styling.ol_depths, self.list_depth);
+                match start {
+                    Some(1) => self.push_elname("ol", &ol_css, &config.classes.ol),
+                    Some(start) => {
+                        self.push_element_styled(
+                            Element::new("ol").attr("start", start),
+                            &ol_css,
+                            &config.classes.ol,
+                        );
+                    }
+                    None => self.push_elname(
+                        "ul",
+                        &at_depth(&styling.ul, &styling.ul_depths, self.list_depth),
+                        &config.classes.ul,
+                    ),
+                }
+            }
+            Tag::Item => {
+                self.item_spans.push(self.span.clone());
+                self.push_hooked(
+                    ElementTag::Item,
+                    "li",
+                    &at_depth(&styling.li, &styling.li_depths, self.list_depth),
+                    &config.classes.li,
+                );
+            }
+            Tag::DefinitionList => self.push_hooked(
+                ElementTag::DefinitionList,
+                "dl",
+                &styling.dl,
+                &config.classes.dl,
+            ),
+            Tag::DefinitionListTitle => self.push_hooked(
+                ElementTag::DefinitionListTitle,
+                "dt",
+                &styling.dt,
+                &config.classes.dt,
+            ),
+            Tag::DefinitionListDefinition => self.push_hooked(
+                ElementTag::DefinitionListDefinition,
+                "dd",
+                &styling.dd,
+                &config.classes.dd,
+            ),
+            Tag::Subscript => self.push_hooked(
+                ElementTag::Subscript,
+                "sub",
+                &styling.sub,
+                &config.classes.sub,
+            ),
+            Tag::Superscript => self.push_hooked(
+                ElementTag::Superscript,
+                "sup",
+                &styling.sup,
+                &config.classes.sup,
+            ),
+            Tag::Emphasis => {
+                self.push_hooked(ElementTag::Emphasis, "em", &styling.em, &config.classes.em)
+            }
+            Tag::Strong => self.push_hooked(
+                ElementTag::Strong,
+                "strong",
+                &styling.strong,
+                &config.classes.strong,
+            ),
+            Tag::Strikethrough => self.push_hooked(
+                ElementTag::Strikethrough,
+                "del",
+                &styling.del,
+                &config.classes.del,
+            ),
             Tag::Link {
                 link_type,
                 dest_url,
                 title,
                 id: _,
             } => {
+                if let Some(element) = self.hooked(ElementTag::Link {
+                    link_type: *link_type,
+                    url: dest_url,
+                    title,
+                }) {
+                    self.push_node(element);
+                    return;
+                }
                 let prefix = match link_type {
                     LinkType::Email => "mailto:",
                     _ => "",
                 };
-                let element = DomElement::new("a").attr("href", [prefix, dest_url].concat());
+                let href = [prefix, dest_url].concat();
+                let mut element = Element::new("a");
+                // Links in markdown are checked the same way as in raw HTML
+                if self.config.html.is_url_allowed(&href) {
+                    element.add_attr("href", href);
+                }
                 if !title.is_empty() {
                     element.add_attr("title", title);
                 }
-                self.push_element_styled(element, &styling.a);
+                self.push_element_styled(element, &styling.a, &config.classes.a);
             }
             Tag::Image {
                 link_type: _,
@@ -282,12 +597,22 @@ where
                 title,
                 id: _,
             } => {
-                let mut element = DomElement::new("img")
-                    .attr("src", dest_url)
-                    .attr("alt", self.raw_text());
-
-                if !styling.img.groups.is_empty() {
-                    element = element.css(&styling.img);
+                let alt = self.raw_text();
+                if let Some(element) = self.hooked(ElementTag::Image {
+                    url: dest_url,
+                    title,
+                    alt: &alt,
+                }) {
+                    self.add_child(element);
+                    return;
+                }
+                let mut element = styled(
+                    Element::new("img").attr("alt", alt),
+                    &styling.img,
+                    &config.classes.img,
+                );
+                if self.config.html.is_url_allowed(dest_url) {
+                    element.add_attr("src", dest_url.to_string());
                 }
                 if !title.is_empty() {
                     element.add_attr("title", title);
@@ -295,30 +620,35 @@ where
                 self.add_child(element);
             }
             Tag::FootnoteDefinition(name) => {
-                let len = self.numbers.len() + 1;
-                let number = *self.numbers.entry(name.clone()).or_insert(len);
-                let mut sup_element = DomElement::new("sup")
-                    .attr("class", "footnote-definition-label")
-                    .child(DomText::new(number.to_string()));
-                if !styling.sup.groups.is_empty() {
-                    sup_element = sup_element.css(&styling.sub);
-                }
-                self.push_node(
-                    DomElement::new("div")
-                        .attr("class", "footnote-definition")
-                        .attr("id", name)
-                        .child(sup_element),
+                self.in_footnote = Some(name.clone());
+                self.push_element_styled(
+                    Element::new("li").attr("id", format!("fn-{}", name)),
+                    &styling.footnote_definition,
+                    &config.classes.footnote_definition,
                 );
             }
-            Tag::MetadataBlock(_) => {
+            Tag::MetadataBlock(kind) => {
                 self.in_non_writing_block = true;
+                if self.front_matter.is_none() {
+                    self.front_matter = Some(FrontMatter {
+                        kind: *kind,
+                        raw: String::new(),
+                    });
+                    self.in_front_matter = true;
+                }
             }
         }
     }
 
     fn end_tag(&mut self, tag: TagEnd) {
+        if !matches!(tag, TagEnd::HtmlBlock | TagEnd::Image) {
+            // Close HTML elements left open inside this tag
+            self.close_html(self.tag_level);
+            self.tag_level = self.tag_level.saturating_sub(1);
+        }
         match tag {
-            TagEnd::HtmlBlock => {}
+            // Raw text element not closed inside HTML block doesn't hide the rest of document
+            TagEnd::HtmlBlock => self.close_html_skip(self.tag_level),
             TagEnd::Table => {
                 // </tbody></table>
                 self.pop_node();
@@ -328,7 +658,12 @@ where
                 // </tr></thead><tbody>
                 self.pop_node();
                 self.pop_node();
-                self.push_elname("tbody", &self.styling.clone().tbody);
+                self.push_hooked(
+                    ElementTag::TableBody,
+                    "tbody",
+                    &self.styling.clone().tbody,
+                    &self.config.clone().classes.tbody,
+                );
                 self.table_state = TableState::Body;
             }
             TagEnd::TableCell => {
@@ -336,23 +671,54 @@ where
                 self.table_cell_index += 1;
             }
             TagEnd::CodeBlock => {
-                // </code> or </code></pre>
-                self.pop_node();
-                #[cfg(feature = "syntect")]
-                {
-                    self.in_code_block = None;
-                }
-                #[cfg(not(feature = "syntect"))]
-                {
+                if let Some((info, source)) = self.in_code_block.take() {
+                    if let Some(renderer) = self.config.code_renderers.get(&info.lang) {
+                        self.add_child(Node::Dom(renderer.render(&info, &source)));
+                        return;
+                    }
+                    for node in self.render_code(&info, &source) {
+                        self.add_child(node);
+                    }
+                    // </code></pre> or </code></pre></figure>
                     self.pop_node();
+                    self.pop_node();
+                    if info.title.is_some() {
+                        self.pop_node();
+                    }
+                }
+            }
+            TagEnd::Heading(_) => {
+                if let Some(heading) = self.ended_heading.take() {
+                    self.end_heading(heading);
+                }
+                self.pop_node();
+            }
+            TagEnd::Paragraph => {
+                let placeholder = self.toc_placeholder.take();
+                match (placeholder, &self.config.toc) {
+                    (Some(text), Some(toc_config)) if is_toc_placeholder(&text) => {
+                        // Replace the paragraph with table of contents
+                        self.soc.pop_front();
+                        let entries = build_toc(&self.toc, toc_config);
+                        let list = toc_list(&entries, &self.styling, &self.config.classes);
+                        self.add_child(list);
+                    }
+                    _ => self.pop_node(),
                 }
             }
+            TagEnd::BlockQuote(_) => {
+                self.blockquote_depth = self.blockquote_depth.saturating_sub(1);
+                self.pop_node();
+            }
+            TagEnd::List(_) => {
+                self.list_depth = self.list_depth.saturating_sub(1);
+                self.pop_node();
+            }
+            TagEnd::Item => {
+                self.item_spans.pop();
+                self.pop_node();
+            }
             TagEnd::TableRow
-            | TagEnd::Paragraph
-            | TagEnd::Heading(_)
-            | TagEnd::BlockQuote(_)
-            | TagEnd::List(_)
-            | TagEnd::Item
             | TagEnd::DefinitionList
             | TagEnd::DefinitionListTitle
             | TagEnd::DefinitionListDefinition
@@ -361,14 +727,119 @@ where
             | TagEnd::Emphasis
             | TagEnd::Strong
             | TagEnd::Strikethrough
-            | TagEnd::Link
-            | TagEnd::FootnoteDefinition => {
+            | TagEnd::Link => {
                 self.pop_node();
             }
+            TagEnd::FootnoteDefinition => {
+                // Definitions are moved to the footnotes section,
+                // ones without references are not numbered and dropped
+                let name = self.in_footnote.take();
+                let number = name
+                    .as_ref()
+                    .and_then(|name| self.numbers.get(name).copied());
+                if let (Some(node), Some(name), Some(number)) = (self.soc.pop_front(), name, number)
+                {
+                    self.footnotes.push((number, name, node))
+                }
+            }
             TagEnd::Image => {} // shouldn't happen, handled in start
             TagEnd::MetadataBlock(_) => {
                 self.in_non_writing_block = false;
+                self.in_front_matter = false;
+            }
+        }
+    }
+
+    /// Renders raw HTML fragment, allowing only tags and attributes permitted by [HtmlPolicy](crate::HtmlPolicy)
+    fn write_html(&mut self, html: &str, block: bool) {
+        let config = self.config.clone();
+        let policy = &config.html;
+
+        for token in tokenize(html) {
+            if let Some((skipped, _)) = &self.html_skip_until {
+                if matches!(&token, HtmlToken::EndTag { name } if name == skipped) {
+                    self.html_skip_until = None;
+                }
+                continue;
+            }
+            match token {
+                HtmlToken::StartTag {
+                    name,
+                    attrs,
+                    self_closing,
+                } => {
+                    let Some(tag) = policy.tag(&name) else {
+                        if is_raw_text_element(&name) && !self_closing {
+                            self.html_skip_until = Some((name, self.tag_level));
+                        }
+                        continue;
+                    };
+                    let mut element = Element::new(tag);
+                    for (attr_name, value) in attrs {
+                        if let Some(attr) = policy.attribute(tag, &attr_name, &value) {
+                            element.add_attr(attr, value);
+                        }
+                    }
+                    if is_void_element(tag) || self_closing {
+                        self.add_child(element);
+                    } else {
+                        self.push_node(element);
+                        self.html_stack.push((tag, self.tag_level));
+                    }
+                }
+                HtmlToken::EndTag { name } => {
+                    // Only elements opened inside current markdown tag can be closed
+                    let position = self
+                        .html_stack
+                        .iter()
+                        .rposition(|(tag, level)| *tag == name && *level == self.tag_level);
+                    if let Some(position) = position {
+                        while self.html_stack.len() > position {
+                            self.html_stack.pop();
+                            self.pop_node();
+                        }
+                    }
+                }
+                HtmlToken::Text(text) => {
+                    // Whitespace between block-level tags is insignificant
+                    if !block || !text.trim().is_empty() {
+                        self.add_child(Node::text(text));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Closes HTML elements opened at given tag level or deeper
+    fn close_html(&mut self, level: usize) {
+        while matches!(self.html_stack.last(), Some((_, el_level)) if *el_level >= level) {
+            self.html_stack.pop();
+            self.pop_node();
+        }
+        self.close_html_skip(level);
+    }
+
+    /// Stops skipping raw text element not closed inside markdown tag it was opened in
+    fn close_html_skip(&mut self, level: usize) {
+        if matches!(self.html_skip_until, Some((_, el_level)) if el_level >= level) {
+            self.html_skip_until = None;
+        }
+    }
+
+    /// Whether the event is a content of dropped raw text element (i.e. `<script>`) and is skipped,
+    /// HTML and block-level tags are still handled to find where the element ends
+    fn skip_raw_text(&mut self, event: &Event) -> bool {
+        match event {
+            Start(tag) if self.html_skip_until.is_some() && is_inline(tag) => {
+                self.html_skipped_tags += 1;
+                true
             }
+            End(tag) if self.html_skipped_tags > 0 && is_inline_end(tag) => {
+                self.html_skipped_tags -= 1;
+                true
+            }
+            Start(_) | End(_) | Html(_) | InlineHtml(_) => false,
+            _ => self.html_skip_until.is_some(),
         }
     }
 
@@ -376,7 +847,7 @@ where
     fn raw_text(&mut self) -> String {
         let mut nest = 0;
         let mut writer = String::new();
-        for event in self.iter.by_ref() {
+        for (event, _) in self.iter.by_ref() {
             match event {
                 Start(_) => nest += 1,
                 End(_) => {
@@ -402,11 +873,10 @@ where
                 SoftBreak | HardBreak | Rule => {
                     writer.push(' ');
                 }
-                FootnoteReference(name) => {
-                    let len = self.numbers.len() + 1;
-                    let number = *self.numbers.entry(name).or_insert(len);
-                    writer.push_str(&format!("[{}]", number));
-                }
+                FootnoteReference(name) => match self.numbers.get(&name) {
+                    Some(number) => writer.push_str(&format!("[{}]", number)),
+                    None => writer.push_str(&format!("[^{}]", name)),
+                },
                 TaskListMarker(true) => {
                     writer.push_str("[x]");
                 }
@@ -418,66 +888,426 @@ where
         writer
     }
 
-    fn push_node(&mut self, node: impl Into<DomNode>) {
-        self.soc.push_front(node.into());
+    /// Checkbox of task list item, enabled if toggling is handled by the app
+    fn task_checkbox(&self, checked: bool) -> Element {
+        let mut element = match (&self.config.task_toggle, self.item_spans.last()) {
+            (Some(on_toggle), Some(item)) => {
+                let on_toggle = on_toggle.clone();
+                let item = item.clone();
+                Element::new("input")
+                    .attr("type", "checkbox")
+                    .on_click(move || on_toggle(item.clone(), !checked))
+            }
+            _ => Element::new("input")
+                .attr("disabled", "")
+                .attr("type", "checkbox"),
+        };
+        if checked {
+            element.add_attr("checked", "checked");
+        }
+        element
     }
 
-    fn push_element_styled(&mut self, element: DomElement, css: &Css) {
-        let mut element = element;
-        if !css.groups.is_empty() {
-            element = element.css(css)
+    /// Renders code block content, split into lines if needed
+    fn render_code(&self, info: &CodeInfo, source: &str) -> Vec<Node> {
+        let by_lines = info.has_line_wrappers();
+
+        #[cfg(feature = "syntect")]
+        let lines = highlight(
+            &self.highlighter(),
+            &self.config.theme,
+            self.config.highlight_mode,
+            &info.lang,
+            source,
+            by_lines,
+        );
+        #[cfg(not(feature = "syntect"))]
+        let lines = plain_lines(source, by_lines);
+
+        if by_lines {
+            wrap_lines(info, lines, &self.styling, &self.config.classes)
+        } else {
+            lines.into_iter().flatten().collect()
         }
-        self.soc.push_front(element.into());
     }
 
-    fn push_elname(&mut self, name: impl Into<String>, css: &Css) {
-        let name = name.into();
-        let mut element = DomElement::new(name);
-        if !css.groups.is_empty() {
-            element = element.css(css)
+    #[cfg(not(feature = "syntect"))]
+    fn push_code_block_pre(&mut self) {
+        self.push_element_styled(
+            Element::new("pre"),
+            &self.styling.clone().codeblock,
+            &self.config.clone().classes.codeblock,
+        );
+    }
+
+    /// Pushes `<pre>` with theme colors or class for highlighted code
+    #[cfg(feature = "syntect")]
+    fn push_code_block_pre(&mut self) {
+        let mut css = self.styling.codeblock.clone();
+        let pre_class = self.config.highlight_mode.pre_class().unwrap_or_default();
+        if pre_class.is_empty() {
+            if let Some(style) = self.config.theme.container_style(&self.highlighter()) {
+                css = Css::string(style).extend(css);
+            }
         }
-        self.push_node(element);
+        let class = join_classes([pre_class.as_str(), &self.config.classes.codeblock]);
+        self.push_element_styled(Element::new("pre"), &css, &class);
     }
 
-    fn pop_node(&mut self) -> Option<DomNode> {
-        if let Some(child) = self.soc.pop_front() {
-            match self.soc.front_mut() {
-                Some(parent) => {
-                    match parent {
-                        DomNode::Node { node } => node.add_child(child),
-                        _ => {
-                            unreachable!("Can't push children to non-element node");
-                        }
+    #[cfg(feature = "syntect")]
+    fn highlighter(&self) -> Arc<HighlighterContext> {
+        self.config
+            .highlighter
+            .clone()
+            .unwrap_or_else(HighlighterContext::shared)
+    }
+
+    /// Writes collected footnote definitions with links back to references
+    fn write_footnotes(&mut self) {
+        let mut footnotes = std::mem::take(&mut self.footnotes);
+        // Like on GitHub, only definitions with references are listed
+        footnotes.retain(|(_, name, _)| self.ref_counts.get(name).is_some_and(|count| *count > 0));
+        if footnotes.is_empty() {
+            return;
+        }
+        footnotes.sort_by_key(|(number, ..)| *number);
+
+        let mut list = styled(
+            Element::new("ol"),
+            &self.styling.footnotes_list,
+            &self.config.classes.footnotes_list,
+        );
+        for (_, name, mut node) in footnotes {
+            if let Node::Element(item) = &mut node {
+                let count = self.ref_counts.get(&name).copied().unwrap_or_default();
+                for index in 1..=count {
+                    let mut backref = Element::new("a")
+                        .attr("href", format!("#{}", footnote_ref_id(&name, index)))
+                        .child_text("\u{21a9}");
+                    if index > 1 {
+                        backref.add_child(styled(
+                            Element::new("sup").child_text(index.to_string()),
+                            &self.styling.sup,
+                            &self.config.classes.sup,
+                        ));
                     }
-                    return None;
+                    item.add_child(styled(
+                        backref,
+                        &self.styling.footnote_backref,
+                        &join_classes(["footnote-backref", &self.config.classes.footnote_backref]),
+                    ));
                 }
-                None => return Some(child),
             }
+            list.add_child(node);
+        }
+        let section = styled(
+            Element::new("section").child(list),
+            &self.styling.footnotes,
+            &join_classes(["footnotes", &self.config.classes.footnotes]),
+        );
+        self.add_child(section);
+    }
+
+    /// Sets generated id of heading on the stack and appends permalink to it
+    fn end_heading(&mut self, heading: Heading) {
+        let generate_ids = self.config.heading_ids || self.config.toc.is_some();
+        let id = if heading.generated {
+            if !generate_ids {
+                return;
+            }
+            self.heading_ids.pop_front().unwrap_or(heading.id)
+        } else {
+            heading.id
+        };
+        if let Some(Node::Element(node)) = self.soc.front_mut() {
+            if heading.generated {
+                node.add_attr("id", id.clone());
+            }
+            if let Some(permalink) = &self.config.heading_permalink {
+                node.add_child(styled(
+                    Element::new("a")
+                        .attr("href", format!("#{}", id))
+                        .child_text(permalink.clone()),
+                    &self.styling.heading_anchor,
+                    &join_classes(["heading-anchor", &self.config.classes.heading_anchor]),
+                ));
+            }
+        }
+    }
+
+    /// Gets element from [render hooks](crate::RenderHooks) if they override it
+    fn hooked(&self, tag: ElementTag) -> Option<Element> {
+        self.config
+            .hooks
+            .as_ref()?
+            .element(&tag)
+            .map(Element::hooked)
+    }
+
+    /// Pushes element from render hooks or the default one
+    fn push_hooked(&mut self, tag: ElementTag, name: &'static str, css: &Css, class: &str) {
+        match self.hooked(tag) {
+            Some(element) => self.push_node(element),
+            None => self.push_elname(name, css, class),
+        }
+    }
+
+    /// Adds source span to the outermost element pushed since stack had `depth` nodes
+    fn mark_source(&mut self, depth: usize) {
+        let Some(idx) = self.soc.len().checked_sub(depth + 1) else {
+            return;
+        };
+        if let Some(Node::Element(element)) = self.soc.get_mut(idx) {
+            add_source_attrs(element, &self.span);
+        }
+    }
+
+    fn push_node(&mut self, node: impl Into<Node>) {
+        self.soc.push_front(node.into());
+    }
+
+    fn push_element_styled(&mut self, element: Element, css: &Css, class: &str) {
+        self.soc.push_front(styled(element, css, class).into());
+    }
+
+    fn push_elname(&mut self, name: &'static str, css: &Css, class: &str) {
+        self.push_node(styled(Element::new(name), css, class));
+    }
+
+    fn pop_node(&mut self) {
+        if let Some(child) = self.soc.pop_front() {
+            self.add_child(child);
         }
-        None
     }
 
-    fn add_child(&mut self, child: impl Into<DomNode>) {
+    fn add_child(&mut self, child: impl Into<Node>) {
         if let Some(parent) = self.soc.front_mut() {
             match parent {
-                DomNode::Node { node } => node.add_child(child),
-                _ => log::error!("Can't push child to non-element node (2)"),
+                Node::Element(element) => element.add_child(child),
+                _ => log::error!("Can't push child to non-element node"),
             }
         } else {
-            log::error!("Can't add child without parent node")
+            self.roots.push(child.into())
         }
     }
 
-    fn add_child_name(&mut self, child_name: impl Into<String>) {
-        self.add_child(DomElement::new(child_name.into()));
+    /// Adds element without children from the source
+    fn add_element_styled(&mut self, element: Element, css: &Css, class: &str) {
+        self.push_element_styled(element, css, class);
+        self.pop_node();
     }
 }
 
+fn is_inline(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. }
+    )
+}
+
+fn is_inline_end(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image
+    )
+}
+
+/// Whether the tag is a block-level element annotated with source positions.
+fn is_block(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Paragraph
+            | Tag::Heading { .. }
+            | Tag::BlockQuote(_)
+            | Tag::CodeBlock(_)
+            | Tag::List(_)
+            | Tag::Item
+            | Tag::FootnoteDefinition(_)
+            | Tag::DefinitionList
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition
+            | Tag::Table(_)
+            | Tag::TableHead
+            | Tag::TableRow
+    )
+}
+
+fn add_source_attrs(element: &mut Element, span: &Range<usize>) {
+    element.add_attr("data-source-start", span.start);
+    element.add_attr("data-source-end", span.end);
+}
+
 /// Iterate over an iterator of pulldown's events, generate DomNode for each `Event`,
 /// structure it into DOM tree and return the root node.
-pub fn generate_tree<'a, I>(iter: I, styling: CMarkStyle) -> DomNode
+pub fn generate_tree<'a, I>(iter: I, styling: CMarkStyle, config: CMarkConfig) -> DomNode
+where
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
+{
+    generate_node(iter, styling, config).into_dom()
+}
+
+/// Same as [generate_tree], but returns also the first metadata block of the document.
+pub fn generate_tree_with_front_matter<'a, I>(
+    iter: I,
+    styling: CMarkStyle,
+    config: CMarkConfig,
+) -> (DomNode, Option<FrontMatter>)
+where
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
+{
+    let (root, front_matter) = document_writer(iter, styling, config).run_with_front_matter();
+    (root.into_dom(), front_matter)
+}
+
+/// Same as [generate_tree], but writes the tree as HTML string.
+///
+/// Render hooks and custom code block renderers are not used, as they produce vertigo elements,
+/// and task checkboxes are always disabled.
+pub fn generate_html<'a, I>(iter: I, styling: CMarkStyle, mut config: CMarkConfig) -> String
+where
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
+{
+    config.hooks = None;
+    config.code_renderers.clear();
+    config.task_toggle = None;
+    let mut html = String::new();
+    generate_node(iter, styling, config).write_html(&mut html);
+    html
+}
+
+/// Writes the whole document into a tree of elements, not converted to vertigo nodes yet.
+pub(crate) fn generate_node<'a, I>(iter: I, styling: CMarkStyle, config: CMarkConfig) -> Node
+where
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
+{
+    document_writer(iter, styling, config).run()
+}
+
+/// Creates writer for the whole document, collecting footnotes and headings upfront.
+fn document_writer<'a, I>(
+    iter: I,
+    styling: CMarkStyle,
+    config: CMarkConfig,
+) -> VertigoWriter<'a, std::vec::IntoIter<(Event<'a>, Range<usize>)>>
+where
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
+{
+    let events = iter.collect::<Vec<_>>();
+    let numbers = footnote_numbers(events.iter().map(|(event, _)| event));
+    let toc = if config.toc.is_some() {
+        let mut headings = Headings::default();
+        events
+            .iter()
+            .filter_map(|(event, _)| headings.event(event))
+            .collect()
+    } else {
+        vec![]
+    };
+    let mut writer = VertigoWriter::new(events.into_iter(), Rc::new(styling), Rc::new(config));
+    writer.numbers = numbers;
+    writer.toc = toc;
+    writer
+}
+
+/// Numbers defined footnotes in order of their first reference (unreferenced ones are skipped).
+pub(crate) fn footnote_numbers<'a, 'e, I>(events: I) -> HashMap<CowStr<'a>, usize>
+where
+    'a: 'e,
+    I: IntoIterator<Item = &'e Event<'a>>,
+    I::IntoIter: Clone,
+{
+    let events = events.into_iter();
+    let defined = events
+        .clone()
+        .filter_map(|event| match event {
+            Start(Tag::FootnoteDefinition(name)) => Some(name),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    let mut numbers = HashMap::new();
+    for event in events {
+        if let FootnoteReference(name) = event {
+            if defined.contains(name) {
+                let len = numbers.len() + 1;
+                numbers.entry(name.clone()).or_insert(len);
+            }
+        }
+    }
+    numbers
+}
+
+/// Id of n-th reference (counted from 1) to the footnote
+fn footnote_ref_id(name: &str, index: usize) -> String {
+    if index == 1 {
+        format!("fnref-{}", name)
+    } else {
+        format!("fnref-{}-{}", name, index)
+    }
+}
+
+/// Document-wide data for writing a fragment of document.
+#[derive(Default)]
+pub(crate) struct FragmentContext<'a> {
+    /// Numbers of defined footnotes referenced in the fragment
+    pub numbers: HashMap<CowStr<'a>, usize>,
+    /// Number of references to footnotes before the fragment
+    pub ref_counts: HashMap<CowStr<'a>, usize>,
+    /// Generated ids of headings in the fragment
+    pub heading_ids: Vec<String>,
+    /// Headings of the whole document for table of contents
+    pub toc: Vec<Heading>,
+    /// Offset of the fragment in the whole source, added to spans of its events
+    pub source_offset: usize,
+}
+
+/// Finds the innermost block-level element containing the offset of markdown source,
+/// returning its span (as in `data-source-start` and `data-source-end` attributes).
+pub fn source_block_at<'a, I>(iter: I, offset: usize) -> Option<Range<usize>>
+where
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
+{
+    iter.filter(|(event, span)| match event {
+        Start(tag) => is_block(tag) && span.contains(&offset),
+        Rule => span.contains(&offset),
+        _ => false,
+    })
+    .map(|(_, span)| span)
+    .last()
+}
+
+/// Generates top-level nodes for a fragment of document.
+pub(crate) fn generate_fragment<'a, I>(
+    iter: I,
+    styling: Rc<CMarkStyle>,
+    config: Rc<CMarkConfig>,
+    context: FragmentContext<'a>,
+) -> Vec<DomNode>
 where
-    I: Iterator<Item = Event<'a>>,
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
 {
-    VertigoWriter::new(iter, styling).run()
+    let offset = context.source_offset;
+    let iter = iter.map(move |(event, span)| (event, span.start + offset..span.end + offset));
+    let mut writer = VertigoWriter::new(iter, styling, config);
+    writer.numbers = context.numbers;
+    writer.ref_counts = context.ref_counts;
+    writer.heading_ids = context.heading_ids.into();
+    writer.toc = context.toc;
+    writer
+        .run_fragment()
+        .into_iter()
+        .map(Node::into_dom)
+        .collect()
 }
@@ -1,60 +1,370 @@
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::sync::{Arc, OnceLock};
 use syntect::easy::HighlightLines;
-use syntect::highlighting::{Color, FontStyle, Style, ThemeSet};
-use syntect::parsing::SyntaxSet;
+use syntect::highlighting::{Color, FontStyle, Style, Theme, ThemeSet};
+use syntect::html::css_for_theme_with_class_style;
+pub use syntect::html::ClassStyle;
+use syntect::parsing::{BasicScopeStackOp, ParseState, Scope, ScopeStack, SyntaxSet};
 use syntect::util::LinesWithEndings;
-use vertigo::DomElement;
+use syntect::LoadingError;
+use vertigo::log;
 
-pub fn highlight(info: &str, s: &str) -> Vec<DomElement> {
-    // Load these once at the start of your program
-    let ps = SyntaxSet::load_defaults_newlines();
-    let ts = ThemeSet::load_defaults();
+use crate::tree::{Element, Node};
+
+/// Name of the theme used when no other is selected or the selected one is missing.
+pub const DEFAULT_THEME: &str = "base16-eighties.dark";
+
+static SHARED_CONTEXT: OnceLock<Arc<HighlighterContext>> = OnceLock::new();
+
+/// Syntax definitions and themes used to highlight code blocks.
+///
+/// Loading them is expensive, so a context should be created once per app and reused.
+/// If no context is provided in [CMarkConfig](crate::CMarkConfig), a [shared](HighlighterContext::shared)
+/// one with syntect defaults is used.
+pub struct HighlighterContext {
+    pub syntax_set: SyntaxSet,
+    pub theme_set: ThemeSet,
+}
+
+impl HighlighterContext {
+    pub fn new(syntax_set: SyntaxSet, theme_set: ThemeSet) -> Self {
+        Self {
+            syntax_set,
+            theme_set,
+        }
+    }
+
+    /// Loads default syntaxes and themes bundled with syntect.
+    pub fn load_defaults() -> Self {
+        Self::new(
+            SyntaxSet::load_defaults_newlines(),
+            ThemeSet::load_defaults(),
+        )
+    }
+
+    /// Returns context with syntect defaults, loaded on first use.
+    pub fn shared() -> Arc<Self> {
+        SHARED_CONTEXT
+            .get_or_init(|| Arc::new(Self::load_defaults()))
+            .clone()
+    }
+}
+
+/// Theme for highlighting code blocks.
+#[derive(Clone)]
+pub enum CodeTheme {
+    /// Theme from [HighlighterContext] by name, i.e. `"InspiredGitHub"` or `"base16-ocean.dark"`
+    Named(String),
+    /// Theme loaded by the app
+    Custom(Arc<Theme>),
+}
+
+impl Default for CodeTheme {
+    fn default() -> Self {
+        Self::Named(DEFAULT_THEME.to_string())
+    }
+}
+
+impl CodeTheme {
+    pub fn named(name: impl Into<String>) -> Self {
+        Self::Named(name.into())
+    }
+
+    /// Loads theme from `.tmTheme` file contents.
+    pub fn from_tm_theme(bytes: &[u8]) -> Result<Self, LoadingError> {
+        let theme = ThemeSet::load_from_reader(&mut Cursor::new(bytes))?;
+        Ok(Self::Custom(Arc::new(theme)))
+    }
+
+    /// Generates stylesheet for code highlighted in [HighlightMode::Classes] mode.
+    ///
+    /// The `<pre>` element of code block gets `code` class (with prefix if set) so the theme
+    /// background and foreground colors are applied to it as well.
+    pub fn stylesheet(
+        &self,
+        ctx: &HighlighterContext,
+        class_style: ClassStyle,
+    ) -> Result<String, syntect::Error> {
+        css_for_theme_with_class_style(&self.resolve(ctx), class_style)
+    }
+
+    /// Inline style for the `<pre>` element with theme background and foreground colors.
+    pub(crate) fn container_style(&self, ctx: &HighlighterContext) -> Option<String> {
+        let theme = self.resolve(ctx);
+        let settings = &theme.settings;
+        let mut declarations = vec![];
+        if let Some(background) = &settings.background {
+            declarations.push(format!("background-color: {}", color_to_css(background)));
+        }
+        if let Some(foreground) = &settings.foreground {
+            declarations.push(format!("color: {}", color_to_css(foreground)));
+        }
+        if declarations.is_empty() {
+            None
+        } else {
+            Some(declarations.join("; "))
+        }
+    }
+
+    /// Gets the theme from context, falling back to [DEFAULT_THEME] (or any available) if named one is missing.
+    pub(crate) fn resolve<'a>(&'a self, ctx: &'a HighlighterContext) -> Cow<'a, Theme> {
+        match self {
+            Self::Custom(theme) => Cow::Borrowed(theme.as_ref()),
+            Self::Named(name) => {
+                let themes = &ctx.theme_set.themes;
+                if let Some(theme) = themes.get(name) {
+                    return Cow::Borrowed(theme);
+                }
+                log::error!("Theme {name} not found, falling back to {DEFAULT_THEME}");
+                themes
+                    .get(DEFAULT_THEME)
+                    .or_else(|| themes.values().next())
+                    .map(Cow::Borrowed)
+                    .unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// How highlighted tokens are styled.
+#[derive(Clone, Copy, Default)]
+pub enum HighlightMode {
+    /// Each token gets `style` attribute with colors from the [CodeTheme]
+    #[default]
+    InlineStyles,
+    /// Tokens get classes named after syntect scopes, colors should be provided
+    /// by a stylesheet generated by [CodeTheme::stylesheet]
+    Classes(ClassStyle),
+}
+
+impl HighlightMode {
+    /// Class of the `<pre>` element for which theme background is generated in the stylesheet
+    pub(crate) fn pre_class(&self) -> Option<String> {
+        match self {
+            Self::InlineStyles => None,
+            Self::Classes(class_style) => Some(format!("{}code", class_prefix(class_style))),
+        }
+    }
+}
+
+/// Highlights the code, returns nodes for every line if `by_lines` is set,
+/// otherwise all nodes are returned as a single line.
+pub fn highlight(
+    ctx: &HighlighterContext,
+    theme: &CodeTheme,
+    mode: HighlightMode,
+    lang: &str,
+    s: &str,
+    by_lines: bool,
+) -> Vec<Vec<Node>> {
+    match mode {
+        HighlightMode::InlineStyles => highlight_styled(ctx, theme, lang, s, by_lines)
+            .into_iter()
+            .map(|line| line.into_iter().map(Node::from).collect())
+            .collect(),
+        HighlightMode::Classes(class_style) => {
+            highlight_classed(ctx, class_style, lang, s, by_lines)
+        }
+    }
+}
+
+pub fn highlight_styled(
+    ctx: &HighlighterContext,
+    theme: &CodeTheme,
+    lang: &str,
+    s: &str,
+    by_lines: bool,
+) -> Vec<Vec<Element>> {
+    let ps = &ctx.syntax_set;
+    let theme = theme.resolve(ctx);
 
     let syntax = ps
-        .find_syntax_by_token(info)
+        .find_syntax_by_token(lang)
         .unwrap_or_else(|| ps.find_syntax_plain_text());
-    let mut h = HighlightLines::new(syntax, &ts.themes["base16-eighties.dark"]);
+    let mut h = HighlightLines::new(syntax, &theme);
 
-    let mut output = vec![];
+    // Join adjacent tokens of the same style to not produce a span for every one of them
+    let mut lines: Vec<Vec<(Style, String)>> = vec![];
+    let mut tokens: Vec<(Style, String)> = vec![];
     for line in LinesWithEndings::from(s) {
         // LinesWithEndings enables use of newlines mode
-        let ranges: Vec<(Style, &str)> = h.highlight_line(line, &ps).unwrap();
-        let line = generate_line(&ranges);
-        output.extend(line);
+        let ranges: Vec<(Style, &str)> = h.highlight_line(line, ps).unwrap();
+        for (style, txt) in ranges {
+            match tokens.last_mut() {
+                Some((last_style, last_txt)) if *last_style == style => last_txt.push_str(txt),
+                _ => tokens.push((style, txt.to_string())),
+            }
+        }
+        if by_lines {
+            lines.push(std::mem::take(&mut tokens));
+        }
     }
-    output
+    if !tokens.is_empty() {
+        lines.push(tokens);
+    }
+
+    lines
+        .iter()
+        .map(|tokens| {
+            tokens
+                .iter()
+                .map(|(style, txt)| generate_span(style, txt, theme.settings.background))
+                .collect()
+        })
+        .collect()
 }
 
-pub fn generate_line(inputs: &[(Style, &str)]) -> Vec<DomElement> {
-    let mut spans = vec![];
-    for (style, txt) in inputs {
-        spans.push(generate_span(style, txt));
+/// Generates spans nested the same way as syntect scopes, each with classes from its scope.
+///
+/// When split `by_lines`, spans open at the end of line are closed and opened again in the next line.
+pub fn highlight_classed(
+    ctx: &HighlighterContext,
+    class_style: ClassStyle,
+    lang: &str,
+    s: &str,
+    by_lines: bool,
+) -> Vec<Vec<Node>> {
+    let ps = &ctx.syntax_set;
+
+    let syntax = ps
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| ps.find_syntax_plain_text());
+    let mut parse_state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+
+    let mut lines = vec![];
+    let mut spans = ClassedSpans::new(class_style);
+    for line in LinesWithEndings::from(s) {
+        let ops = parse_state.parse_line(line, ps).unwrap();
+        let mut pos = 0;
+        for (idx, op) in ops {
+            if idx > pos {
+                spans.add(Node::text(&line[pos..idx]));
+                pos = idx;
+            }
+            stack
+                .apply_with_hook(&op, |basic_op, _| match basic_op {
+                    BasicScopeStackOp::Push(scope) => spans.open(scope),
+                    BasicScopeStackOp::Pop => spans.close(),
+                })
+                .unwrap();
+        }
+        if pos < line.len() {
+            spans.add(Node::text(&line[pos..]));
+        }
+        if by_lines {
+            lines.push(spans.finish_line());
+        }
     }
-    spans
+    if !by_lines {
+        let rest = spans.finish();
+        if !rest.is_empty() {
+            lines.push(rest);
+        }
+    }
+    lines
 }
 
-pub fn generate_span(style: &Style, txt: &str) -> DomElement {
-    let color = color_to_css(&style.foreground);
-    let background_color = color_to_css(&style.background);
-    let mut style_value = format!("color: {color}; background_color: {background_color}");
+struct ClassedSpans {
+    class_style: ClassStyle,
+    open: Vec<(Scope, Element)>,
+    output: Vec<Node>,
+}
+
+impl ClassedSpans {
+    fn new(class_style: ClassStyle) -> Self {
+        Self {
+            class_style,
+            open: vec![],
+            output: vec![],
+        }
+    }
+
+    fn add(&mut self, node: impl Into<Node>) {
+        match self.open.last_mut() {
+            Some((_, parent)) => parent.add_child(node),
+            None => self.output.push(node.into()),
+        }
+    }
+
+    fn open(&mut self, scope: Scope) {
+        let prefix = class_prefix(&self.class_style);
+        let classes = scope
+            .build_string()
+            .split('.')
+            .map(|atom| format!("{prefix}{atom}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.open
+            .push((scope, Element::new("span").attr("class", classes)));
+    }
 
-    if !style.font_style.is_empty() {
-        if style.font_style.contains(FontStyle::BOLD) {
-            style_value.push_str(" font-weight: bold;");
+    fn close(&mut self) {
+        if let Some((_, span)) = self.open.pop() {
+            self.add(span);
         }
-        if style.font_style.contains(FontStyle::ITALIC) {
-            style_value.push_str(" font-style: italic;");
+    }
+
+    /// Closes all spans returning the line, and opens them again for the next one
+    fn finish_line(&mut self) -> Vec<Node> {
+        let scopes = self
+            .open
+            .iter()
+            .map(|(scope, _)| *scope)
+            .collect::<Vec<_>>();
+        let class_style = self.class_style;
+        let line = std::mem::replace(self, Self::new(class_style)).finish();
+        for scope in scopes {
+            self.open(scope);
         }
-        if style.font_style.contains(FontStyle::UNDERLINE) {
-            style_value.push_str(" text-decoration: underline;");
+        line
+    }
+
+    fn finish(mut self) -> Vec<Node> {
+        while !self.open.is_empty() {
+            self.close();
         }
+        self.output
+    }
+}
+
+fn class_prefix(class_style: &ClassStyle) -> &'static str {
+    if let ClassStyle::SpacedPrefixed { prefix } = class_style {
+        prefix
+    } else {
+        ""
+    }
+}
+
+/// Generates span for a token, background is omitted if it's the same as theme background
+/// (which is already set on the `<pre>` element).
+pub fn generate_span(style: &Style, txt: &str, theme_background: Option<Color>) -> Element {
+    let mut declarations = vec![format!("color: {}", color_to_css(&style.foreground))];
+
+    if theme_background != Some(style.background) {
+        declarations.push(format!(
+            "background-color: {}",
+            color_to_css(&style.background)
+        ));
+    }
+    if style.font_style.contains(FontStyle::BOLD) {
+        declarations.push("font-weight: bold".to_string());
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        declarations.push("font-style: italic".to_string());
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        declarations.push("text-decoration: underline".to_string());
     }
 
-    DomElement::new("span")
-        .attr("style", style_value)
+    Element::new("span")
+        .attr("style", declarations.join("; "))
         .child_text(txt)
 }
 
 pub fn color_to_css(color: &Color) -> String {
     let Color { r, g, b, a } = color;
-    format!("rgba({r}, {g}, {b}, {a})")
+    // CSS expects alpha in 0..1 range, while syntect keeps it as a byte
+    format!("rgba({r}, {g}, {b}, {})", *a as f32 / 255.0)
 }
@@ -0,0 +1,79 @@
+use pulldown_cmark::{Alignment, BlockQuoteKind, HeadingLevel, LinkType};
+use vertigo::DomElement;
+
+/// Element which is about to be created, with data from markdown source.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ElementTag<'a> {
+    Paragraph,
+    Heading {
+        level: HeadingLevel,
+        id: Option<&'a str>,
+        classes: Vec<&'a str>,
+    },
+    BlockQuote {
+        kind: Option<BlockQuoteKind>,
+    },
+    List {
+        start: Option<u64>,
+    },
+    Item,
+    Table {
+        alignments: &'a [Alignment],
+    },
+    TableHead,
+    TableBody,
+    TableRow,
+    TableCell {
+        head: bool,
+        alignment: Alignment,
+    },
+    DefinitionList,
+    DefinitionListTitle,
+    DefinitionListDefinition,
+    Emphasis,
+    Strong,
+    Strikethrough,
+    Subscript,
+    Superscript,
+    Link {
+        link_type: LinkType,
+        url: &'a str,
+        title: &'a str,
+    },
+    Image {
+        url: &'a str,
+        title: &'a str,
+        alt: &'a str,
+    },
+}
+
+/// Allows to replace elements created by the writer with own ones, i.e. components of a design system.
+///
+/// Returned element is used as a container, the writer adds children to it (except for images).
+/// Styling from [CMarkStyle](crate::CMarkStyle) is not applied to such element.
+/// Closures can be used as hooks as well:
+///
+/// ```rust
+/// use vertigo::DomElement;
+/// use vertigo_cmark::{CMarkConfig, ElementTag};
+///
+/// let config = CMarkConfig::default().with_hooks(|tag: &ElementTag| match tag {
+///     ElementTag::Link { url, .. } => {
+///         Some(DomElement::new("a").attr("href", *url).attr("class", "router-link"))
+///     }
+///     _ => None,
+/// });
+/// ```
+pub trait RenderHooks {
+    /// Returns element to use instead of the default one, or `None` to keep the default
+    fn element(&self, tag: &ElementTag) -> Option<DomElement>;
+}
+
+impl<F> RenderHooks for F
+where
+    F: Fn(&ElementTag) -> Option<DomElement>,
+{
+    fn element(&self, tag: &ElementTag) -> Option<DomElement> {
+        self(tag)
+    }
+}
@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+/// Elements which never have children nor closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is not markup and should be dropped together with them.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title", "iframe", "noscript"];
+
+/// Attributes which contain URLs and therefore are checked against allowed schemes.
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "cite"];
+
+/// Allowlist of HTML tags and attributes which can be rendered from raw HTML in CommonMark source.
+///
+/// Everything not on the list is dropped (the text content of unknown tags is preserved,
+/// except for `<script>`, `<style>` and similar elements which are removed entirely).
+/// Event handlers (`on*` attributes) are never rendered, and URL attributes are checked
+/// against allowed [schemes](HtmlPolicy::url_schemes).
+///
+/// ```rust
+/// use vertigo_cmark::HtmlPolicy;
+///
+/// let policy = HtmlPolicy::default()
+///     .allow_tag("video", &["src", "controls"])
+///     .deny_tag("img");
+/// ```
+#[derive(Clone)]
+pub struct HtmlPolicy {
+    /// Allowed tags with list of attributes allowed for each tag
+    pub tags: HashMap<&'static str, Vec<&'static str>>,
+    /// Attributes allowed on every allowed tag
+    pub generic_attributes: Vec<&'static str>,
+    /// Schemes allowed in URL attributes (`href`, `src`, `cite`), relative URLs are always allowed
+    pub url_schemes: Vec<&'static str>,
+}
+
+impl Default for HtmlPolicy {
+    fn default() -> Self {
+        let mut policy = Self {
+            tags: HashMap::new(),
+            generic_attributes: vec!["title", "lang", "dir"],
+            url_schemes: vec!["http", "https", "mailto"],
+        };
+        for tag in [
+            "abbr",
+            "b",
+            "blockquote",
+            "br",
+            "caption",
+            "cite",
+            "code",
+            "dd",
+            "del",
+            "div",
+            "dl",
+            "dt",
+            "em",
+            "figcaption",
+            "figure",
+            "h1",
+            "h2",
+            "h3",
+            "h4",
+            "h5",
+            "h6",
+            "hr",
+            "i",
+            "ins",
+            "kbd",
+            "li",
+            "mark",
+            "p",
+            "pre",
+            "q",
+            "s",
+            "samp",
+            "small",
+            "span",
+            "strong",
+            "sub",
+            "summary",
+            "sup",
+            "table",
+            "tbody",
+            "tfoot",
+            "thead",
+            "tr",
+            "u",
+            "ul",
+            "var",
+            "wbr",
+        ] {
+            policy.tags.insert(tag, vec![]);
+        }
+        policy
+            .allow_tag("a", &["href"])
+            .allow_tag("details", &["open"])
+            .allow_tag("img", &["src", "alt", "width", "height"])
+            .allow_tag("ol", &["start", "reversed"])
+            .allow_tag("td", &["colspan", "rowspan", "align"])
+            .allow_tag("th", &["colspan", "rowspan", "align"])
+    }
+}
+
+impl HtmlPolicy {
+    /// Policy that doesn't allow any tag, so raw HTML is dropped (only text inside it is rendered).
+    pub fn deny_all() -> Self {
+        Self {
+            tags: HashMap::new(),
+            generic_attributes: vec![],
+            url_schemes: vec![],
+        }
+    }
+
+    /// Allows a tag with provided attributes (attributes are added to already allowed ones).
+    pub fn allow_tag(mut self, tag: &'static str, attributes: &[&'static str]) -> Self {
+        self.tags
+            .entry(tag)
+            .or_default()
+            .extend(attributes.iter().copied());
+        self
+    }
+
+    /// Removes a tag from allowed ones.
+    pub fn deny_tag(mut self, tag: &str) -> Self {
+        self.tags.remove(tag);
+        self
+    }
+
+    pub(crate) fn tag(&self, name: &str) -> Option<&'static str> {
+        self.tags.get_key_value(name).map(|(tag, _)| *tag)
+    }
+
+    pub(crate) fn attribute(&self, tag: &str, name: &str, value: &str) -> Option<&'static str> {
+        // Event handlers are never allowed, even if someone puts them on the list
+        if name.starts_with("on") {
+            return None;
+        }
+        let attr = self
+            .tags
+            .get(tag)
+            .and_then(|attrs| attrs.iter().find(|attr| **attr == name))
+            .or_else(|| self.generic_attributes.iter().find(|attr| **attr == name))?;
+        if URL_ATTRIBUTES.contains(attr) && !self.is_url_allowed(value) {
+            return None;
+        }
+        Some(attr)
+    }
+
+    /// Whether URL has no scheme or one of allowed [schemes](HtmlPolicy::url_schemes).
+    pub(crate) fn is_url_allowed(&self, url: &str) -> bool {
+        // Browsers ignore whitespace and control characters in scheme, i.e. "java\tscript:"
+        let url = url
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match url.find([':', '/', '?', '#']) {
+            Some(idx) if url[idx..].starts_with(':') => {
+                self.url_schemes.iter().any(|scheme| *scheme == &url[..idx])
+            }
+            _ => true,
+        }
+    }
+}
+
+pub(crate) fn is_void_element(name: &str) -> bool {
+    VOID_ELEMENTS.contains(&name)
+}
+
+pub(crate) fn is_raw_text_element(name: &str) -> bool {
+    RAW_TEXT_ELEMENTS.contains(&name)
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum HtmlToken {
+    StartTag {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    EndTag {
+        name: String,
+    },
+    Text(String),
+}
+
+/// Splits a fragment of HTML into tags and (entity-decoded) text.
+///
+/// This is not a full HTML5 parser, only enough to handle what one puts into markdown.
+/// Comments, doctypes and processing instructions are skipped.
+pub(crate) fn tokenize(input: &str) -> Vec<HtmlToken> {
+    let mut tokens = vec![];
+    let mut text = String::new();
+    let mut rest = input;
+
+    while let Some(idx) = rest.find('<') {
+        text.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let skipped = if rest.starts_with("<!--") {
+            Some(rest.find("-->").map(|end| end + 3).unwrap_or(rest.len()))
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            Some(rest.find('>').map(|end| end + 1).unwrap_or(rest.len()))
+        } else {
+            None
+        };
+        if let Some(len) = skipped {
+            rest = &rest[len..];
+            continue;
+        }
+
+        match parse_tag(rest) {
+            Some((token, len)) => {
+                if !text.is_empty() {
+                    tokens.push(HtmlToken::Text(decode_entities(&text)));
+                    text.clear();
+                }
+                tokens.push(token);
+                rest = &rest[len..];
+            }
+            None => {
+                text.push('<');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push(HtmlToken::Text(decode_entities(&text)));
+    }
+
+    tokens
+}
+
+/// Parses a tag at the beginning of input, returns the token and number of consumed bytes.
+fn parse_tag(input: &str) -> Option<(HtmlToken, usize)> {
+    let (closing, mut pos) = if input.starts_with("</") {
+        (true, 2)
+    } else {
+        (false, 1)
+    };
+
+    let name_len = input[pos..]
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .unwrap_or(input.len() - pos);
+    if name_len == 0 || !input[pos..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = input[pos..pos + name_len].to_ascii_lowercase();
+    pos += name_len;
+
+    if closing {
+        let end = input[pos..].find('>')?;
+        return Some((HtmlToken::EndTag { name }, pos + end + 1));
+    }
+
+    let mut attrs = vec![];
+    let mut self_closing = false;
+    loop {
+        let rest = &input[pos..];
+        let trimmed = rest.trim_start();
+        pos += rest.len() - trimmed.len();
+
+        let mut chars = trimmed.chars();
+        match chars.next()? {
+            '>' => {
+                pos += 1;
+                break;
+            }
+            '/' => {
+                pos += 1;
+                self_closing = input[pos..].starts_with('>');
+            }
+            _ => {
+                let attr_len = trimmed
+                    .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '>' || c == '/')
+                    .unwrap_or(trimmed.len());
+                let attr_name = trimmed[..attr_len].to_ascii_lowercase();
+                pos += attr_len;
+
+                let rest = &input[pos..];
+                let after_name = rest.trim_start();
+                let value = if let Some(after_eq) = after_name.strip_prefix('=') {
+                    let value_start = after_eq.trim_start();
+                    pos += rest.len() - value_start.len();
+                    let (value, len) = match value_start.chars().next()? {
+                        quote @ ('"' | '\'') => {
+                            let end = value_start[1..].find(quote)?;
+                            (&value_start[1..end + 1], end + 2)
+                        }
+                        _ => {
+                            let end = value_start
+                                .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                                .unwrap_or(value_start.len());
+                            (&value_start[..end], end)
+                        }
+                    };
+                    pos += len;
+                    decode_entities(value)
+                } else {
+                    String::new()
+                };
+                attrs.push((attr_name, value));
+            }
+        }
+    }
+
+    Some((
+        HtmlToken::StartTag {
+            name,
+            attrs,
+            self_closing,
+        },
+        pos,
+    ))
+}
+
+/// Decodes basic named entities and numeric character references.
+pub(crate) fn decode_entities(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(idx) = rest.find('&') {
+        output.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => {
+                    if let Some(hex) = entity
+                        .strip_prefix("#x")
+                        .or_else(|| entity.strip_prefix("#X"))
+                    {
+                        u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                    } else if let Some(dec) = entity.strip_prefix('#') {
+                        dec.parse().ok().and_then(char::from_u32)
+                    } else {
+                        None
+                    }
+                }
+            };
+            ch.map(|ch| (ch, end + 1))
+        });
+
+        match decoded {
+            Some((ch, len)) => {
+                output.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
@@ -1,13 +1,44 @@
-pub use pulldown_cmark::Options;
 use pulldown_cmark::Parser;
+pub use pulldown_cmark::{
+    Alignment, BlockQuoteKind, CodeBlockKind, CowStr, Event, HeadingLevel, LinkType,
+    MetadataBlockKind, Options, Tag,
+};
+use std::ops::Range;
 use vertigo::DomNode;
 
+mod classes;
+mod code;
+mod config;
+mod document;
+mod front_matter;
 mod generate;
+mod hooks;
+mod html;
+mod plain_text;
+mod reactive;
+mod slug;
+mod stream;
 mod styling;
+mod toc;
+mod tree;
+pub use classes::CMarkClasses;
+pub use code::{CodeBlockRenderer, CodeInfo};
+pub use config::{CMarkConfig, TaskToggle};
+pub use document::{DemoteHeadings, Document, DocumentNode, RewriteLinks, Transform};
+pub use front_matter::FrontMatter;
+#[cfg(feature = "front-matter")]
+pub use front_matter::MetaValue;
+pub use hooks::{ElementTag, RenderHooks};
+pub use html::HtmlPolicy;
+pub use reactive::Markdown;
+pub use stream::MarkdownStream;
 pub use styling::CMarkStyle;
+pub use toc::{toc, toc_to_vertigo, TocConfig, TocEntry};
 
 #[cfg(feature = "syntect")]
 mod highlighting;
+#[cfg(feature = "syntect")]
+pub use highlighting::{ClassStyle, CodeTheme, HighlightMode, HighlighterContext, DEFAULT_THEME};
 
 #[cfg(test)]
 mod tests;
@@ -22,13 +53,17 @@ pub fn to_vertigo(text: &str) -> DomNode {
 /// Converts a CommonMark string to Vertigo tree with provided [Options] and default styling.
 pub fn to_vertigo_opts(text: &str, opts: Options) -> DomNode {
     let parser = Parser::new_ext(text, opts);
-    generate::generate_tree(parser, CMarkStyle::default())
+    generate::generate_tree(
+        parser.into_offset_iter(),
+        CMarkStyle::default(),
+        CMarkConfig::default(),
+    )
 }
 
 /// Converts a CommonMark string to Vertigo tree with default options and provided [styling](CMarkStyle).
 pub fn to_vertigo_styled(text: &str, style: CMarkStyle) -> DomNode {
     let parser = Parser::new_ext(text, Options::ENABLE_TABLES);
-    generate::generate_tree(parser, style)
+    generate::generate_tree(parser.into_offset_iter(), style, CMarkConfig::default())
 }
 
 /// Converts a CommonMark string to Vertigo tree with provided [Options] and provided [styling](CMarkStyle).
@@ -36,5 +71,163 @@ pub fn to_vertigo_styled(text: &str, style: CMarkStyle) -> DomNode {
 /// NOTE: If you want highlighted code block, just enable `syntect` feature.
 pub fn to_vertigo_opts_styled(text: &str, opts: Options, style: CMarkStyle) -> DomNode {
     let parser = Parser::new_ext(text, opts);
-    generate::generate_tree(parser, style)
+    generate::generate_tree(parser.into_offset_iter(), style, CMarkConfig::default())
+}
+
+/// Converts a CommonMark string to Vertigo tree with provided [Options], [styling](CMarkStyle)
+/// and [configuration](CMarkConfig).
+pub fn to_vertigo_with_config(
+    text: &str,
+    opts: Options,
+    style: CMarkStyle,
+    config: CMarkConfig,
+) -> DomNode {
+    let parser = Parser::new_ext(text, opts);
+    generate::generate_tree(parser.into_offset_iter(), style, config)
+}
+
+/// Converts a CommonMark string to Vertigo tree like [to_vertigo_with_config], returning also
+/// the front matter (first metadata block) of the document.
+///
+/// NOTE: Both YAML (`---`) and TOML (`+++`) style metadata blocks are enabled.
+///
+/// ```rust
+/// use vertigo_cmark::{to_vertigo_with_front_matter, CMarkConfig, CMarkStyle, Options};
+///
+/// let (content, front_matter) = to_vertigo_with_front_matter(
+///     "---\ntitle: Hello\n---\n\n# Hello",
+///     Options::empty(),
+///     CMarkStyle::default(),
+///     CMarkConfig::default(),
+/// );
+/// assert_eq!(front_matter.unwrap().raw, "title: Hello\n");
+/// ```
+pub fn to_vertigo_with_front_matter(
+    text: &str,
+    opts: Options,
+    style: CMarkStyle,
+    config: CMarkConfig,
+) -> (DomNode, Option<FrontMatter>) {
+    let opts = opts
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+        | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS;
+    let parser = Parser::new_ext(text, opts);
+    generate::generate_tree_with_front_matter(parser.into_offset_iter(), style, config)
+}
+
+/// Converts a CommonMark string to HTML string with provided [Options], [styling](CMarkStyle)
+/// and [configuration](CMarkConfig), i.e. for server-side rendering or RSS feeds.
+///
+/// The output matches the tree created by [to_vertigo_with_config], with styles written
+/// as inline `style` attributes (media queries are skipped). Render hooks and custom code block
+/// renderers are not used as they produce vertigo elements.
+///
+/// ```rust
+/// use vertigo_cmark::{to_html_string, CMarkConfig, CMarkStyle, Options};
+///
+/// let html = to_html_string(
+///     "Fish & *chips* < 5",
+///     Options::empty(),
+///     CMarkStyle::default(),
+///     CMarkConfig::default(),
+/// );
+/// assert_eq!(html, "<div><p>Fish &amp; <em>chips</em> &lt; 5</p></div>");
+/// ```
+pub fn to_html_string(text: &str, opts: Options, style: CMarkStyle, config: CMarkConfig) -> String {
+    let parser = Parser::new_ext(text, opts);
+    generate::generate_html(parser.into_offset_iter(), style, config)
+}
+
+/// Converts a CommonMark string to plain text with provided [Options], i.e. for previews,
+/// search indexing or notifications.
+///
+/// Blocks are separated with empty lines, list items get bullets or numbers, table cells
+/// are separated with tabs, links are written as `text (url)` and footnote references as `[1]`.
+/// Code is preserved, while raw HTML and metadata blocks are skipped.
+///
+/// If `limit` is provided, text is cut at word boundary to have at most that many characters,
+/// including the trailing `…`.
+///
+/// ```rust
+/// use vertigo_cmark::{to_plain_text, Options};
+///
+/// let text = to_plain_text("# Hello\n\nSee [the docs](https://docs.rs) *now*", Options::empty(), None);
+/// assert_eq!(text, "Hello\n\nSee the docs (https://docs.rs) now");
+///
+/// let text = to_plain_text("Lorem ipsum dolor sit amet", Options::empty(), Some(15));
+/// assert_eq!(text, "Lorem ipsum…");
+/// ```
+pub fn to_plain_text(text: &str, opts: Options, limit: Option<usize>) -> String {
+    let parser = Parser::new_ext(text, opts);
+    plain_text::generate_plain_text(parser, limit)
+}
+
+/// Finds the innermost block-level element containing the byte offset of markdown source
+/// and returns its span, i.e. to scroll preview rendered with
+/// [source_positions](CMarkConfig::source_positions) to the element with matching
+/// `data-source-start` attribute.
+///
+/// ```rust
+/// use vertigo_cmark::{source_block_at, Options};
+///
+/// let source = "# Title\n\n- foo\n- bar\n";
+/// assert_eq!(source_block_at(source, Options::empty(), 2), Some(0..8));
+/// assert_eq!(source_block_at(source, Options::empty(), 17), Some(15..21));
+/// assert_eq!(source_block_at(source, Options::empty(), 8), None);
+/// ```
+pub fn source_block_at(text: &str, opts: Options, offset: usize) -> Option<Range<usize>> {
+    let parser = Parser::new_ext(text, opts);
+    generate::source_block_at(parser.into_offset_iter(), offset)
+}
+
+/// Returns markdown source with task marker of the list item at `item` span set to the new state,
+/// i.e. to update the source in [task_toggle](CMarkConfig::task_toggle) callback.
+///
+/// Source is returned unchanged if the span doesn't contain `[ ]` or `[x]` marker.
+///
+/// ```rust
+/// use vertigo::{DomNode, Value};
+/// use vertigo_cmark::{toggle_task, CMarkConfig, Markdown, Options};
+///
+/// let source = Value::new("- [ ] foo\n- [x] bar\n".to_string());
+/// let config = CMarkConfig::default().with_task_toggle({
+///     let source = source.clone();
+///     move |item, checked| source.change(|text| *text = toggle_task(text, item, checked))
+/// });
+/// let preview: DomNode = Markdown {
+///     opts: Options::ENABLE_TASKLISTS,
+///     config,
+///     ..Markdown::new(source.to_computed())
+/// }
+/// .mount();
+///
+/// assert_eq!(toggle_task("- [ ] foo\n- [x] bar\n", 10..20, false), "- [ ] foo\n- [ ] bar\n");
+/// ```
+pub fn toggle_task(source: &str, item: Range<usize>, checked: bool) -> String {
+    match source.get(item.clone()).and_then(task_marker) {
+        Some(idx) => {
+            let start = item.start + idx;
+            let marker = if checked { "[x]" } else { "[ ]" };
+            [&source[..start], marker, &source[start + 3..]].concat()
+        }
+        None => source.to_string(),
+    }
+}
+
+/// Offset of task marker following the list item bullet or number
+fn task_marker(source: &str) -> Option<usize> {
+    let item = source.trim_start();
+    let after_bullet = item.strip_prefix(['-', '*', '+']).or_else(|| {
+        let after_number = item.trim_start_matches(|c: char| c.is_ascii_digit());
+        if after_number.len() < item.len() {
+            after_number.strip_prefix(['.', ')'])
+        } else {
+            None
+        }
+    })?;
+    let marker = after_bullet.trim_start_matches([' ', '\t']);
+    match marker.get(..3) {
+        Some("[ ]" | "[x]" | "[X]") => Some(source.len() - marker.len()),
+        _ => None,
+    }
 }
@@ -0,0 +1,298 @@
+use pulldown_cmark::{CowStr, Event, Event::*, LinkType, Tag, TagEnd};
+use std::collections::HashMap;
+
+use crate::generate::footnote_numbers;
+
+/// Writes events as readable text, keeping the structure of blocks with line breaks,
+/// list markers and blockquote prefixes.
+struct PlainTextWriter<'a, I> {
+    /// Iterator supplying events.
+    iter: I,
+
+    out: String,
+    /// Numbers of defined footnotes, references to other names are written as they are
+    numbers: HashMap<CowStr<'a>, usize>,
+
+    /// Prefixes of lines within nested blockquotes, list items and footnote definitions
+    indent: Vec<String>,
+    /// Marker of list item or footnote definition to be written before its first text
+    marker: Option<String>,
+    /// Numbers of next items in nested lists, `None` for bullet lists
+    lists: Vec<Option<u64>>,
+    /// Line breaks to be written before the next text
+    breaks: usize,
+    /// Nesting level of the outermost block requesting line breaks, used for empty lines
+    breaks_level: usize,
+
+    /// Whether inside a metadata block (text should not be written)
+    in_non_writing_block: bool,
+    /// Code block being written, its content is written at the end of block
+    in_code_block: Option<String>,
+    /// Destinations of links being written, appended after their text
+    links: Vec<Option<CowStr<'a>>>,
+    table_cell_index: usize,
+}
+
+impl<'a, I> PlainTextWriter<'a, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    fn new(iter: I, numbers: HashMap<CowStr<'a>, usize>) -> Self {
+        Self {
+            iter,
+            out: String::new(),
+            numbers,
+            indent: vec![],
+            marker: None,
+            lists: vec![],
+            breaks: 0,
+            breaks_level: 0,
+            in_non_writing_block: false,
+            in_code_block: None,
+            links: vec![],
+            table_cell_index: 0,
+        }
+    }
+
+    fn run(mut self) -> String {
+        while let Some(event) = self.iter.next() {
+            match event {
+                Start(tag) => self.start_tag(tag),
+                End(tag) => self.end_tag(tag),
+                Text(_) if self.in_non_writing_block => {}
+                Text(text) => {
+                    if let Some(source) = &mut self.in_code_block {
+                        source.push_str(&text);
+                    } else {
+                        self.write(&text);
+                    }
+                }
+                Code(text) => self.write(&text),
+                InlineMath(text) => self.write(&format!("${}$", text)),
+                DisplayMath(text) => self.write(&format!("$${}$$", text)),
+                Html(_) | InlineHtml(_) => {}
+                SoftBreak => self.write(" "),
+                HardBreak => self.line_breaks(1),
+                Rule => {
+                    self.line_breaks(2);
+                    self.write("---");
+                    self.line_breaks(2);
+                }
+                FootnoteReference(name) => match self.numbers.get(&name) {
+                    Some(number) => self.write(&format!("[{}]", number)),
+                    None => self.write(&format!("[^{}]", name)),
+                },
+                TaskListMarker(true) => self.write("[x] "),
+                TaskListMarker(false) => self.write("[ ] "),
+            }
+        }
+        self.out
+    }
+
+    fn start_tag(&mut self, tag: Tag<'a>) {
+        match tag {
+            Tag::Paragraph
+            | Tag::Heading { .. }
+            | Tag::Table(_)
+            | Tag::DefinitionList
+            | Tag::HtmlBlock => self.line_breaks(2),
+            Tag::BlockQuote(_) => {
+                self.line_breaks(2);
+                self.indent.push("> ".to_string());
+            }
+            Tag::CodeBlock(_) => {
+                self.line_breaks(2);
+                self.in_code_block = Some(String::new());
+            }
+            Tag::List(start) => {
+                self.line_breaks(if self.lists.is_empty() { 2 } else { 1 });
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.line_breaks(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let marker = format!("{}. ", number);
+                        *number += 1;
+                        marker
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.start_marked(marker);
+            }
+            Tag::FootnoteDefinition(name) => {
+                self.line_breaks(2);
+                let marker = match self.numbers.get(&name) {
+                    Some(number) => format!("[{}] ", number),
+                    None => format!("[^{}] ", name),
+                };
+                self.start_marked(marker);
+            }
+            Tag::DefinitionListTitle => self.line_breaks(1),
+            Tag::DefinitionListDefinition => {
+                self.line_breaks(1);
+                self.start_marked(": ".to_string());
+            }
+            Tag::TableHead | Tag::TableRow => {
+                self.line_breaks(1);
+                self.table_cell_index = 0;
+            }
+            Tag::TableCell => {
+                if self.table_cell_index > 0 {
+                    self.write("\t");
+                }
+                self.table_cell_index += 1;
+            }
+            Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            } => {
+                // Autolinks already have the destination as text
+                let dest_url = match link_type {
+                    LinkType::Autolink | LinkType::Email => None,
+                    _ if dest_url.is_empty() => None,
+                    _ => Some(dest_url),
+                };
+                self.links.push(dest_url);
+            }
+            Tag::MetadataBlock(_) => {
+                self.in_non_writing_block = true;
+            }
+            Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Image { .. } => {}
+        }
+    }
+
+    fn end_tag(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph
+            | TagEnd::Heading(_)
+            | TagEnd::Table
+            | TagEnd::DefinitionList
+            | TagEnd::HtmlBlock => self.line_breaks(2),
+            TagEnd::BlockQuote(_) => {
+                self.indent.pop();
+                self.line_breaks(2);
+            }
+            TagEnd::CodeBlock => {
+                let source = self.in_code_block.take().unwrap_or_default();
+                for (index, line) in source.trim_end_matches('\n').split('\n').enumerate() {
+                    if index > 0 {
+                        self.line_breaks(1);
+                    }
+                    self.write(line);
+                }
+                self.line_breaks(2);
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                self.line_breaks(if self.lists.is_empty() { 2 } else { 1 });
+            }
+            TagEnd::Item | TagEnd::DefinitionListDefinition => {
+                self.end_marked();
+                self.line_breaks(1);
+            }
+            TagEnd::FootnoteDefinition => {
+                self.end_marked();
+                self.line_breaks(2);
+            }
+            TagEnd::DefinitionListTitle | TagEnd::TableHead | TagEnd::TableRow => {
+                self.line_breaks(1)
+            }
+            TagEnd::Link => {
+                if let Some(Some(dest_url)) = self.links.pop() {
+                    self.write(&format!(" ({})", dest_url));
+                }
+            }
+            TagEnd::MetadataBlock(_) => {
+                self.in_non_writing_block = false;
+            }
+            TagEnd::TableCell
+            | TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Image => {}
+        }
+    }
+
+    /// Starts block with marker written before its first line, next lines are indented
+    fn start_marked(&mut self, marker: String) {
+        self.indent.push(" ".repeat(marker.chars().count()));
+        self.marker = Some(marker);
+    }
+
+    fn end_marked(&mut self) {
+        self.indent.pop();
+        self.marker = None;
+    }
+
+    /// Requests line breaks before the next text, two for separating blocks
+    fn line_breaks(&mut self, count: usize) {
+        if self.breaks == 0 {
+            self.breaks_level = self.indent.len();
+        }
+        self.breaks = self.breaks.max(count);
+        self.breaks_level = self.breaks_level.min(self.indent.len());
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.breaks > 0 && !self.out.is_empty() {
+            // Line with the pending marker is not indented by its own block
+            let levels = self.indent.len() - usize::from(self.marker.is_some());
+            let empty_prefix = self.indent[..self.breaks_level.min(levels)].concat();
+            for _ in 1..self.breaks {
+                self.out.push('\n');
+                self.out.push_str(empty_prefix.trim_end());
+            }
+            self.out.push('\n');
+            self.out.push_str(&self.indent[..levels].concat());
+        }
+        self.breaks = 0;
+        if let Some(marker) = self.marker.take() {
+            self.out.push_str(&marker);
+        }
+        self.out.push_str(text);
+    }
+}
+
+/// Cuts the text at word boundary so it has at most `limit` characters including the ellipsis.
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let Some(limit) = limit.checked_sub(1) else {
+        return String::new();
+    };
+    let end = text
+        .char_indices()
+        .nth(limit)
+        .map(|(idx, _)| idx)
+        .unwrap_or(text.len());
+    let cut = if text[end..].starts_with(char::is_whitespace) {
+        end
+    } else {
+        text[..end].rfind(char::is_whitespace).unwrap_or(end)
+    };
+    format!("{}…", text[..cut].trim_end())
+}
+
+/// Writes events as plain text, optionally truncated to the `limit` of characters.
+pub(crate) fn generate_plain_text<'a, I>(iter: I, limit: Option<usize>) -> String
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    let events = iter.collect::<Vec<_>>();
+    let numbers = footnote_numbers(&events);
+    let text = PlainTextWriter::new(events.into_iter(), numbers).run();
+    match limit {
+        Some(limit) => truncate(&text, limit),
+        None => text,
+    }
+}
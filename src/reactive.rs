@@ -0,0 +1,382 @@
+use pulldown_cmark::{CowStr, Event, Parser, Tag};
+use std::{collections::HashMap, ops::Range, rc::Rc};
+use vertigo::{render::render_list, Computed, DomComment, DomElement, DomNode, ToComputed};
+
+use crate::{
+    config::CMarkConfig,
+    generate::{footnote_numbers, generate_fragment, FragmentContext},
+    html::{is_void_element, tokenize, HtmlToken},
+    styling::CMarkStyle,
+    toc::{has_toc_placeholder, Heading, Headings},
+    Options,
+};
+
+/// Component rendering CommonMark source which can change over time, i.e. in editor preview.
+///
+/// Source is split into top-level blocks and only blocks which have changed are rendered again,
+/// the rest of the tree is left untouched.
+///
+/// ```rust
+/// use vertigo::{DomNode, Value};
+/// use vertigo_cmark::Markdown;
+///
+/// let source = Value::new("# Title\n\nSome *text*".to_string());
+///
+/// let preview: DomNode = Markdown::new(source.to_computed()).mount();
+///
+/// source.set("# Title\n\nOther *text*".to_string());
+/// ```
+pub struct Markdown {
+    pub source: Computed<String>,
+    pub opts: Options,
+    pub style: CMarkStyle,
+    pub config: CMarkConfig,
+}
+
+impl Markdown {
+    /// Creates component with default options (tables enabled), styling and configuration.
+    pub fn new(source: impl ToComputed<String>) -> Self {
+        Self {
+            source: source.to_computed(),
+            opts: Options::ENABLE_TABLES,
+            style: CMarkStyle::default(),
+            config: CMarkConfig::default(),
+        }
+    }
+
+    pub fn mount(self) -> DomNode {
+        let opts = self.opts;
+        // Spans are passed to task toggles too
+        let positions = self.config.source_positions || self.config.task_toggle.is_some();
+        let blocks = self
+            .source
+            .map(move |source| split_blocks(&source, opts, positions));
+        render_blocks(blocks, self.style, self.config)
+    }
+}
+
+/// Renders blocks in container, re-rendering only blocks which have changed.
+pub(crate) fn render_blocks(
+    blocks: Computed<Vec<Block>>,
+    style: CMarkStyle,
+    config: CMarkConfig,
+) -> DomNode {
+    let style = Rc::new(style);
+    let config = Rc::new(config);
+
+    let container = DomElement::new("div");
+    let container = if style.container.groups.is_empty() {
+        container
+    } else {
+        container.css(&style.container)
+    };
+    if !config.classes.container.is_empty() {
+        container.add_attr("class", config.classes.container.clone());
+    }
+
+    container
+        .child(render_list(
+            &blocks,
+            |block| (block.source.clone(), block.occurrence),
+            move |block| {
+                let style = style.clone();
+                let config = config.clone();
+                block.render_value(move |block| block.render(&style, &config))
+            },
+        ))
+        .into()
+}
+
+/// Top-level block of the document (or several blocks kept together by unclosed HTML).
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Block {
+    /// Markdown source of the block
+    pub source: String,
+    /// Number of preceding blocks with the same source
+    pub occurrence: usize,
+    events: Rc<Vec<Event<'static>>>,
+    /// Spans of events relative to the start of the block
+    spans: Rc<Vec<Range<usize>>>,
+    /// Offset of the block in the whole source, so moved blocks are rendered again
+    offset: usize,
+    /// Document-wide numbers of defined footnotes used in this block
+    footnotes: Vec<(CowStr<'static>, usize)>,
+    /// Number of references to footnotes of this block preceding it
+    ref_counts: Vec<(CowStr<'static>, usize)>,
+    /// Headings in this block, with ids unique in the document
+    headings: Vec<Heading>,
+    /// Whether this block contains `[TOC]` placeholder
+    has_toc: bool,
+    /// Headings of the whole document, set if the block contains `[TOC]` placeholder
+    toc: Option<Rc<Vec<Heading>>>,
+}
+
+impl Block {
+    pub(crate) fn new(source: &str, occurrence: usize, offset: usize, parsed: ParsedBlock) -> Self {
+        Self {
+            source: source.to_string(),
+            occurrence,
+            has_toc: has_toc_placeholder(&parsed.events),
+            events: Rc::new(parsed.events),
+            spans: Rc::new(parsed.spans),
+            offset,
+            footnotes: vec![],
+            ref_counts: vec![],
+            headings: parsed.headings,
+            toc: None,
+        }
+    }
+
+    fn render(&self, style: &Rc<CMarkStyle>, config: &Rc<CMarkConfig>) -> DomNode {
+        let mut nodes = generate_fragment(
+            self.events.iter().cloned().zip(self.spans.iter().cloned()),
+            style.clone(),
+            config.clone(),
+            FragmentContext {
+                numbers: self.footnotes.iter().cloned().collect(),
+                ref_counts: self.ref_counts.iter().cloned().collect(),
+                heading_ids: self
+                    .headings
+                    .iter()
+                    .filter(|heading| heading.generated)
+                    .map(|heading| heading.id.clone())
+                    .collect(),
+                toc: self
+                    .toc
+                    .as_ref()
+                    .map(|toc| toc.as_ref().clone())
+                    .unwrap_or_default(),
+                source_offset: self.offset,
+            },
+        );
+        if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            DomComment::dom_fragment(nodes).into()
+        }
+    }
+}
+
+/// Splits markdown into top-level blocks, numbering footnotes across the whole document.
+///
+/// Offsets of blocks are kept only with `positions`, otherwise blocks moved
+/// in the source compare equal and aren't rendered again.
+pub(crate) fn split_blocks(text: &str, opts: Options, positions: bool) -> Vec<Block> {
+    let mut occurrences = HashMap::new();
+    let mut blocks = parse_blocks(text, opts, &mut DocumentState::default(), false)
+        .into_iter()
+        .map(|parsed| {
+            let source = &text[parsed.range.clone()];
+            let occurrence = next_occurrence(&mut occurrences, source);
+            let offset = if positions { parsed.range.start } else { 0 };
+            Block::new(source, occurrence, offset, parsed)
+        })
+        .collect::<Vec<_>>();
+    attach_toc(&mut blocks);
+    attach_footnotes(blocks)
+}
+
+/// Events of a top-level block with its position in the source.
+pub(crate) struct ParsedBlock {
+    pub range: Range<usize>,
+    events: Vec<Event<'static>>,
+    /// Spans of events relative to the start of the block
+    spans: Vec<Range<usize>>,
+    headings: Vec<Heading>,
+}
+
+/// Ids of headings in the part of document parsed so far.
+#[derive(Clone, Default)]
+pub(crate) struct DocumentState {
+    headings: Headings,
+}
+
+/// Parses markdown into top-level blocks (or into one block if `whole` is set),
+/// continuing ids of headings from `state`.
+pub(crate) fn parse_blocks(
+    text: &str,
+    opts: Options,
+    state: &mut DocumentState,
+    whole: bool,
+) -> Vec<ParsedBlock> {
+    let mut blocks = vec![];
+
+    let mut events = vec![];
+    let mut spans = vec![];
+    let mut headings = vec![];
+    let mut range: Option<Range<usize>> = None;
+    let mut depth = 0_usize;
+    let mut open_html = 0_usize;
+
+    for (event, event_range) in Parser::new_ext(text, opts).into_offset_iter() {
+        let block_range = range.get_or_insert(event_range.clone());
+        block_range.start = block_range.start.min(event_range.start);
+        block_range.end = block_range.end.max(event_range.end);
+
+        headings.extend(state.headings.event(&event));
+
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            // HTML elements left open in a top-level HTML block wrap following blocks
+            Event::Html(html) if depth == 1 => open_html = html_balance(open_html, html),
+            _ => {}
+        }
+        events.push(event.into_static());
+        spans.push(event_range);
+
+        if depth == 0 && open_html == 0 && !whole {
+            if let Some(range) = range.take() {
+                blocks.push(ParsedBlock {
+                    spans: relative_spans(std::mem::take(&mut spans), range.start),
+                    range,
+                    events: std::mem::take(&mut events),
+                    headings: std::mem::take(&mut headings),
+                });
+            }
+        }
+    }
+
+    // Unclosed HTML takes the rest of the document
+    if let Some(range) = range {
+        blocks.push(ParsedBlock {
+            spans: relative_spans(spans, range.start),
+            range,
+            events,
+            headings,
+        });
+    }
+
+    blocks
+}
+
+fn relative_spans(spans: Vec<Range<usize>>, start: usize) -> Vec<Range<usize>> {
+    spans
+        .into_iter()
+        .map(|span| span.start - start..span.end - start)
+        .collect()
+}
+
+/// Provides headings of the whole document to blocks with `[TOC]` placeholder.
+pub(crate) fn attach_toc(blocks: &mut [Block]) {
+    if !blocks.iter().any(|block| block.has_toc) {
+        return;
+    }
+    let headings = Rc::new(
+        blocks
+            .iter()
+            .flat_map(|block| block.headings.iter().cloned())
+            .collect::<Vec<_>>(),
+    );
+    for block in blocks.iter_mut().filter(|block| block.has_toc) {
+        block.toc = Some(headings.clone());
+    }
+}
+
+/// Numbers footnotes across the document and moves top-level footnote definitions
+/// to a block at the end, rendered as footnotes section.
+pub(crate) fn attach_footnotes(blocks: Vec<Block>) -> Vec<Block> {
+    let numbers = footnote_numbers(blocks.iter().flat_map(|block| block.events.iter()));
+    if numbers.is_empty() {
+        return blocks;
+    }
+
+    let (mut blocks, definitions): (Vec<_>, Vec<_>) = blocks.into_iter().partition(|block| {
+        !matches!(
+            block.events.first(),
+            Some(Event::Start(Tag::FootnoteDefinition(_)))
+        )
+    });
+
+    let mut ref_counts = HashMap::new();
+    for block in blocks.iter_mut() {
+        block.footnotes = vec![];
+        block.ref_counts = vec![];
+        for event in block.events.iter() {
+            if let Event::Start(Tag::FootnoteDefinition(name)) | Event::FootnoteReference(name) =
+                event
+            {
+                if let Some(number) = numbers.get(name) {
+                    if !block.footnotes.iter().any(|(used, _)| used == name) {
+                        block.footnotes.push((name.clone(), *number));
+                        let count = ref_counts.get(name).copied().unwrap_or_default();
+                        block.ref_counts.push((name.clone(), count));
+                    }
+                }
+                if let Event::FootnoteReference(name) = event {
+                    *ref_counts.entry(name.clone()).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    if !definitions.is_empty() {
+        let offset = definitions[0].offset;
+        let mut footnotes = numbers.into_iter().collect::<Vec<_>>();
+        footnotes.sort_by_key(|(_, number)| *number);
+        let ref_counts = footnotes
+            .iter()
+            .map(|(name, _)| {
+                let count = ref_counts.get(name).copied().unwrap_or_default();
+                (name.clone(), count)
+            })
+            .collect();
+        blocks.push(Block {
+            source: definitions
+                .iter()
+                .map(|block| block.source.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            occurrence: 0,
+            events: Rc::new(
+                definitions
+                    .iter()
+                    .flat_map(|block| block.events.iter().cloned())
+                    .collect(),
+            ),
+            // Definitions keep their spans relative to the first one
+            spans: Rc::new(
+                definitions
+                    .iter()
+                    .flat_map(|block| {
+                        let shift = block.offset - offset;
+                        block
+                            .spans
+                            .iter()
+                            .map(move |span| span.start + shift..span.end + shift)
+                    })
+                    .collect(),
+            ),
+            offset,
+            footnotes,
+            ref_counts,
+            headings: definitions
+                .iter()
+                .flat_map(|block| block.headings.iter().cloned())
+                .collect(),
+            has_toc: false,
+            toc: None,
+        });
+    }
+    blocks
+}
+
+/// Returns number of previous occurrences of the source and counts this one.
+pub(crate) fn next_occurrence(occurrences: &mut HashMap<String, usize>, source: &str) -> usize {
+    let occurrence = occurrences.entry(source.to_string()).or_default();
+    *occurrence += 1;
+    *occurrence - 1
+}
+
+/// Updates the number of open HTML elements after a fragment of HTML.
+fn html_balance(open: usize, html: &str) -> usize {
+    tokenize(html)
+        .into_iter()
+        .fold(open, |open, token| match token {
+            HtmlToken::StartTag {
+                name, self_closing, ..
+            } if !self_closing && !is_void_element(&name) => open + 1,
+            HtmlToken::EndTag { .. } => open.saturating_sub(1),
+            _ => open,
+        })
+}
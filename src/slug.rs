@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+/// Converts heading text to a slug the way GitHub does: lowercase, without punctuation,
+/// with spaces replaced by hyphens.
+pub(crate) fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            _ if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Slugs used in a document, so duplicates get `-1`, `-2`, ... suffixes.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Slugs {
+    occurrences: HashMap<String, usize>,
+}
+
+impl Slugs {
+    /// Returns unique slug for the heading text.
+    pub fn unique(&mut self, text: &str) -> String {
+        let original = slugify(text);
+        let mut slug = original.clone();
+        while self.occurrences.contains_key(&slug) {
+            let count = self.occurrences.entry(original.clone()).or_default();
+            *count += 1;
+            slug = format!("{}-{}", original, count);
+        }
+        self.occurrences.insert(slug.clone(), 0);
+        slug
+    }
+
+    /// Marks explicitly set id as used.
+    pub fn register(&mut self, id: &str) {
+        self.occurrences.entry(id.to_string()).or_default();
+    }
+}
@@ -0,0 +1,267 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use vertigo::{DomNode, Value};
+
+use crate::{
+    config::CMarkConfig,
+    reactive::{
+        attach_footnotes, attach_toc, next_occurrence, parse_blocks, render_blocks, Block,
+        DocumentState,
+    },
+    styling::CMarkStyle,
+    Options,
+};
+
+/// Renderer of markdown arriving in chunks, i.e. answer of a chat bot streamed token by token.
+///
+/// Completed top-level blocks are rendered once, only the trailing block is rendered again
+/// when text is appended. Until the stream is [finished](MarkdownStream::finish), constructs
+/// left open at the end (emphasis, code spans, code fences, table delimiter row) are closed
+/// in the rendered output, so it doesn't flicker between raw markers and formatted text.
+///
+/// NOTE: Reference link and footnote definitions only apply to blocks not completed before they arrive.
+///
+/// ```rust
+/// use vertigo_cmark::{MarkdownStream, Options};
+///
+/// let stream = MarkdownStream::new(Options::ENABLE_TABLES);
+/// let answer = stream.render();
+///
+/// stream.push("Hello **wor");
+/// stream.push("ld**!");
+/// stream.finish();
+/// ```
+#[derive(Clone)]
+pub struct MarkdownStream {
+    opts: Options,
+    state: Rc<RefCell<StreamState>>,
+    blocks: Value<Vec<Block>>,
+}
+
+#[derive(Default)]
+struct StreamState {
+    text: String,
+    finished: bool,
+    /// Offset of the trailing block which can still change
+    tail_start: usize,
+    completed: Vec<Block>,
+    occurrences: HashMap<String, usize>,
+    document: DocumentState,
+}
+
+impl MarkdownStream {
+    pub fn new(opts: Options) -> Self {
+        Self {
+            opts,
+            state: Rc::new(RefCell::new(StreamState::default())),
+            blocks: Value::new(vec![]),
+        }
+    }
+
+    /// Appends a chunk of markdown.
+    pub fn push(&self, chunk: &str) {
+        self.state.borrow_mut().text.push_str(chunk);
+        self.update();
+    }
+
+    /// Marks the stream as complete, so the trailing block is rendered as is.
+    pub fn finish(&self) {
+        self.state.borrow_mut().finished = true;
+        self.update();
+    }
+
+    /// Markdown received so far.
+    pub fn text(&self) -> String {
+        self.state.borrow().text.clone()
+    }
+
+    /// Renders the stream with default styling and configuration.
+    pub fn render(&self) -> DomNode {
+        self.render_with_config(CMarkStyle::default(), CMarkConfig::default())
+    }
+
+    /// Renders the stream with provided [styling](CMarkStyle) and [configuration](CMarkConfig).
+    pub fn render_with_config(&self, style: CMarkStyle, config: CMarkConfig) -> DomNode {
+        render_blocks(self.blocks.to_computed(), style, config)
+    }
+
+    fn update(&self) {
+        let mut guard = self.state.borrow_mut();
+        let state = &mut *guard;
+
+        // Every block followed by another one is complete
+        let tail = &state.text[state.tail_start..];
+        let mut parsed = parse_blocks(tail, self.opts, &mut state.document.clone(), false);
+        let last_start = parsed.pop().map(|last| last.range.start).unwrap_or(0);
+        if !parsed.is_empty() {
+            // Parse completed blocks again to keep state of the document right after them
+            let completed = &tail[..last_start];
+            for block in parse_blocks(completed, self.opts, &mut state.document, false) {
+                let source = &completed[block.range.clone()];
+                let occurrence = next_occurrence(&mut state.occurrences, source);
+                // Completed blocks don't move, their offsets don't cause rendering again
+                let offset = state.tail_start + block.range.start;
+                state
+                    .completed
+                    .push(Block::new(source, occurrence, offset, block));
+            }
+            state.tail_start += last_start;
+        }
+
+        let mut blocks = state.completed.clone();
+        let tail = &state.text[state.tail_start..];
+        let rendered = if state.finished {
+            tail.to_string()
+        } else {
+            complete_tail(tail, self.opts)
+        };
+        let mut document = state.document.clone();
+        if let Some(block) = parse_blocks(&rendered, self.opts, &mut document, true).pop() {
+            let occurrence = state.occurrences.get(tail).copied().unwrap_or_default();
+            let offset = state.tail_start + block.range.start;
+            blocks.push(Block::new(tail, occurrence, offset, block));
+        }
+        attach_toc(&mut blocks);
+        drop(guard);
+
+        self.blocks.set(attach_footnotes(blocks));
+    }
+}
+
+/// Closes constructs left open at the end of partially arrived block,
+/// so it's rendered the way it most likely will be when complete.
+pub(crate) fn complete_tail(tail: &str, opts: Options) -> String {
+    let (body, last_line) = match tail.rfind('\n') {
+        Some(idx) => (&tail[..idx + 1], &tail[idx + 1..]),
+        None => ("", tail),
+    };
+
+    if let Some(fence) = open_fence(tail) {
+        // Last line may be a closing fence which hasn't fully arrived yet
+        let last_trimmed = last_line.trim();
+        if !last_trimmed.is_empty() && last_trimmed.chars().all(|c| c == fence) {
+            return body.to_string();
+        }
+        return tail.to_string();
+    }
+
+    if opts.contains(Options::ENABLE_TABLES) {
+        if let Some(table) = complete_delimiter_row(body, last_line) {
+            return table;
+        }
+    }
+
+    close_inline(tail, opts)
+}
+
+/// Returns fence character if the block is a fenced code block without closing fence.
+fn open_fence(block: &str) -> Option<char> {
+    let mut lines = block.lines().skip_while(|line| line.trim().is_empty());
+    let opening = lines.next()?.trim_start();
+    let fence = opening.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = opening.chars().take_while(|c| *c == fence).count();
+    if len < 3 {
+        return None;
+    }
+
+    let closed = lines.any(|line| {
+        let line = line.trim();
+        line.len() >= len && line.chars().all(|c| c == fence)
+    });
+    (!closed).then_some(fence)
+}
+
+/// Completes delimiter row of a table which has only the header row yet.
+fn complete_delimiter_row(body: &str, last_line: &str) -> Option<String> {
+    let header = body.trim_start_matches('\n').strip_suffix('\n')?;
+    let delimiter = last_line.trim();
+    if header.contains('\n')
+        || !header.contains('|')
+        || !delimiter.contains('-')
+        || !delimiter
+            .chars()
+            .all(|c| matches!(c, '|' | '-' | ':' | ' '))
+    {
+        return None;
+    }
+
+    let trimmed = header.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix('|').unwrap_or(trimmed);
+    let columns = trimmed.split('|').count();
+
+    Some(format!("{}\n|{}", header, "---|".repeat(columns)))
+}
+
+/// Closes emphasis, strikethrough and code spans opened in the last paragraph of the block.
+fn close_inline(block: &str, opts: Options) -> String {
+    let paragraph_start = block
+        .trim_end()
+        .rfind("\n\n")
+        .map(|idx| idx + 2)
+        .unwrap_or(0);
+    let paragraph = &block[paragraph_start..];
+    let bytes = paragraph.as_bytes();
+
+    // Opened markers with their positions
+    let mut open: Vec<(&str, usize)> = vec![];
+    let mut code_span: Option<(usize, usize)> = None;
+
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let ch = bytes[idx];
+        let run = bytes[idx..].iter().take_while(|c| **c == ch).count();
+        match ch {
+            b'\\' => {
+                idx += 2;
+                continue;
+            }
+            b'`' => match code_span {
+                Some((_, len)) if len == run => code_span = None,
+                Some(_) => {}
+                None => code_span = Some((idx, run)),
+            },
+            b'*' | b'_' | b'~' if code_span.is_none() => {
+                let marker = &paragraph[idx..idx + run.min(3)];
+                let before = paragraph[..idx].chars().next_back();
+                let after = paragraph[idx + run..].chars().next();
+                let is_marker = match ch {
+                    b'~' => run == 2 && opts.contains(Options::ENABLE_STRIKETHROUGH),
+                    // Underscores inside words don't emphasize
+                    b'_' => !before.map(char::is_alphanumeric).unwrap_or(false),
+                    _ => true,
+                };
+                if is_marker {
+                    if open.last().map(|(m, _)| *m == marker).unwrap_or(false)
+                        && !before.map(char::is_whitespace).unwrap_or(true)
+                    {
+                        open.pop();
+                    } else if !after.map(char::is_whitespace).unwrap_or(false) {
+                        open.push((marker, idx));
+                    }
+                }
+            }
+            _ => {}
+        }
+        idx += run;
+    }
+
+    let mut text = paragraph.trim_end().to_string();
+    let mut closing = String::new();
+    if let Some((pos, len)) = code_span {
+        if pos + len >= text.len() {
+            text.truncate(pos);
+        } else {
+            closing.push_str(&"`".repeat(len));
+        }
+    }
+    for (marker, pos) in open.into_iter().rev() {
+        if pos + marker.len() >= text.len() && closing.is_empty() {
+            // Marker at the very end doesn't open anything yet
+            text.truncate(pos);
+        } else {
+            closing.push_str(marker);
+        }
+    }
+
+    format!("{}{}{}", &block[..paragraph_start], text, closing)
+}
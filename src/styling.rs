@@ -1,25 +1,78 @@
 use vertigo::Css;
 
-#[derive(Clone)]
+use crate::tree::Element;
+
+#[derive(Clone, Default)]
 pub struct CMarkStyle {
+    /// Added to `blockquote` style of `[!CAUTION]` alert
+    pub alert_caution: Css,
+    /// Added to `blockquote` style of `[!IMPORTANT]` alert
+    pub alert_important: Css,
+    /// Added to `blockquote` style of `[!NOTE]` alert
+    pub alert_note: Css,
+    /// Added to `blockquote` style of `[!TIP]` alert
+    pub alert_tip: Css,
+    /// Added to `blockquote` style of `[!WARNING]` alert
+    pub alert_warning: Css,
     pub blockquote: Css,
+    /// Styles added to `blockquote` by nesting depth, see [CMarkStyle::ol_depths]
+    pub blockquote_depths: Vec<Css>,
+    pub br: Css,
+    /// Inline code span
+    pub code: Css,
     pub codeblock: Css,
+    /// `<code>` inside `<pre>` of code block
+    pub codeblock_code: Css,
+    /// Figure wrapping code block with a title
+    pub code_figure: Css,
+    /// Line of code block split into lines, i.e. with line numbers (`span.line`)
+    pub code_line: Css,
+    /// Number of line in code block (`span.line-number`)
+    pub code_line_number: Css,
+    /// Title of code block
+    pub code_title: Css,
     pub container: Css,
     pub dd: Css,
     pub del: Css,
     pub dl: Css,
     pub dt: Css,
     pub em: Css,
+    /// Link back to reference at the end of footnote definition
+    pub footnote_backref: Css,
+    /// Footnote definition item in the footnotes section
+    pub footnote_definition: Css,
+    /// Link with number of the footnote inside its reference
+    pub footnote_label: Css,
+    /// Superscript with link to the footnote
+    pub footnote_reference: Css,
+    /// Section with footnote definitions at the end of the document
+    pub footnotes: Css,
+    /// List of footnote definitions in the footnotes section
+    pub footnotes_list: Css,
     pub h1: Css,
     pub h2: Css,
     pub h3: Css,
     pub h4: Css,
     pub h5: Css,
     pub h6: Css,
+    /// Permalink added to headings (see `CMarkConfig::heading_permalink`)
+    pub heading_anchor: Css,
+    pub hr: Css,
     pub img: Css,
+    /// Task list checkbox
+    pub input: Css,
     pub li: Css,
+    /// Styles added to `li` by depth of its list, see [CMarkStyle::ol_depths]
+    pub li_depths: Vec<Css>,
     pub a: Css,
+    /// Display math span (`$$...$$`)
+    pub math_display: Css,
+    /// Inline math span (`$...$`)
+    pub math_inline: Css,
     pub ol: Css,
+    /// Styles added to `ol` by nesting depth of lists (first one for top-level list).
+    /// Used cyclically when lists are nested deeper, so two styles alternate
+    pub ol_depths: Vec<Css>,
     pub p: Css,
     pub strong: Css,
     pub sub: Css,
@@ -29,42 +82,268 @@ pub struct CMarkStyle {
     pub td: Css,
     pub th: Css,
     pub thead: Css,
+    /// Added to `ul` style of table of contents (nested lists use `ul`, `li` and `a` styles)
+    pub toc: Css,
     pub tr: Css,
     pub ul: Css,
+    /// Styles added to `ul` by nesting depth of lists, see [CMarkStyle::ol_depths]
+    pub ul_depths: Vec<Css>,
+}
+
+/// Style for element at nesting depth (counted from 1) with depth styles cycled.
+pub(crate) fn at_depth(css: &Css, depths: &[Css], depth: usize) -> Css {
+    if depths.is_empty() || depth == 0 {
+        return css.clone();
+    }
+    css.clone()
+        .extend(depths[(depth - 1) % depths.len()].clone())
+}
+
+/// Applies css and class (both may be empty) to the element.
+pub(crate) fn styled(element: Element, css: &Css, class: &str) -> Element {
+    let mut element = element;
+    if !css.groups.is_empty() {
+        element = element.css(css);
+    }
+    if !class.is_empty() {
+        element.add_attr("class", class.to_string());
+    }
+    element
+}
+
+/// Joins class names skipping empty ones.
+pub(crate) fn join_classes<'c>(classes: impl IntoIterator<Item = &'c str>) -> String {
+    classes
+        .into_iter()
+        .filter(|class| !class.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Colors of GitHub-like presets
+struct Palette {
+    fg: &'static str,
+    muted: &'static str,
+    border: &'static str,
+    link: &'static str,
+    bg: &'static str,
+    code_bg: &'static str,
+    pre_bg: &'static str,
+    note: &'static str,
+    tip: &'static str,
+    important: &'static str,
+    warning: &'static str,
+    caution: &'static str,
 }
 
-impl Default for CMarkStyle {
-    fn default() -> Self {
+const GITHUB_LIGHT: Palette = Palette {
+    fg: "#1f2328",
+    muted: "#59636e",
+    border: "#d1d9e0",
+    link: "#0969da",
+    bg: "#ffffff",
+    code_bg: "#818b981f",
+    pre_bg: "#f6f8fa",
+    note: "#0969da",
+    tip: "#1a7f37",
+    important: "#8250df",
+    warning: "#9a6700",
+    caution: "#d1242f",
+};
+
+const GITHUB_DARK: Palette = Palette {
+    fg: "#f0f6fc",
+    muted: "#9198a1",
+    border: "#3d444d",
+    link: "#4493f8",
+    bg: "#0d1117",
+    code_bg: "#656c7633",
+    pre_bg: "#151b23",
+    note: "#1f6feb",
+    tip: "#238636",
+    important: "#8957e5",
+    warning: "#9e6a03",
+    caution: "#da3633",
+};
+
+const SANS_FONT: &str =
+    "-apple-system, BlinkMacSystemFont, \"Segoe UI\", \"Noto Sans\", Helvetica, Arial, sans-serif";
+const MONO_FONT: &str = "ui-monospace, SFMono-Regular, \"SF Mono\", Menlo, Consolas, monospace";
+
+impl CMarkStyle {
+    /// Preset resembling markdown rendered by GitHub (light mode),
+    /// see [CMarkConfig::github](crate::CMarkConfig::github) for matching code theme.
+    pub fn github() -> Self {
+        Self::github_palette(&GITHUB_LIGHT)
+    }
+
+    /// Preset resembling markdown rendered by GitHub in dark mode,
+    /// see [CMarkConfig::github_dark](crate::CMarkConfig::github_dark) for matching code theme.
+    pub fn github_dark() -> Self {
+        Self::github_palette(&GITHUB_DARK)
+    }
+
+    /// Light preset only fixing readability: fonts, spacing, tables and code blocks,
+    /// see [CMarkConfig::minimal](crate::CMarkConfig::minimal) for matching code theme.
+    pub fn minimal() -> Self {
+        let alert = |color: &str| Css::string(format!("border-left-color: {};", color));
+        Self {
+            alert_caution: alert("#c62828"),
+            alert_important: alert("#6a1b9a"),
+            alert_note: alert("#1565c0"),
+            alert_tip: alert("#2e7d32"),
+            alert_warning: alert("#ef6c00"),
+            blockquote: Css::str(
+                "margin: 0 0 1em; padding: 0 1em; color: #555; border-left: 3px solid #ddd;",
+            ),
+            code: Css::string(format!("font-family: {}; font-size: 90%;", MONO_FONT)),
+            codeblock: Css::str(
+                "margin: 0 0 1em; padding: 0.75em; overflow: auto; background-color: #f5f5f5;",
+            ),
+            codeblock_code: Css::string(format!("font-family: {}; font-size: 90%;", MONO_FONT)),
+            container: Css::string(format!(
+                "font-family: {}; line-height: 1.6; color: #222;",
+                SANS_FONT
+            )),
+            hr: Css::str("border: 0; border-top: 1px solid #ddd; margin: 1.5em 0;"),
+            img: Css::str("max-width: 100%;"),
+            table: Css::str("border-collapse: collapse; margin: 0 0 1em;"),
+            td: Css::str("padding: 4px 8px; border: 1px solid #ddd;"),
+            th: Css::str("padding: 4px 8px; border: 1px solid #ddd; font-weight: 600;"),
+            ..Default::default()
+        }
+    }
+
+    fn github_palette(palette: &Palette) -> Self {
+        let heading = |size: &str, underline: bool| {
+            let mut css = format!(
+                "margin: 24px 0 16px; font-weight: 600; line-height: 1.25; font-size: {};",
+                size
+            );
+            if underline {
+                css.push_str(&format!(
+                    " padding-bottom: 0.3em; border-bottom: 1px solid {};",
+                    palette.border
+                ));
+            }
+            Css::string(css)
+        };
+        let alert = |color: &str| Css::string(format!("border-left-color: {};", color));
+        let cell = format!("padding: 6px 13px; border: 1px solid {};", palette.border);
+        let list = Css::str("margin: 0 0 16px; padding-left: 2em;");
+
         Self {
-            blockquote: Css::default(),
-            codeblock: Css::default(),
-            container: Css::default(),
-            dd: Css::default(),
+            alert_caution: alert(palette.caution),
+            alert_important: alert(palette.important),
+            alert_note: alert(palette.note),
+            alert_tip: alert(palette.tip),
+            alert_warning: alert(palette.warning),
+            a: Css::string(format!("color: {}; text-decoration: none;", palette.link)),
+            blockquote: Css::string(format!(
+                "margin: 0 0 16px; padding: 0 1em; color: {}; border-left: 0.25em solid {};",
+                palette.muted, palette.border
+            )),
+            blockquote_depths: vec![],
+            br: Css::default(),
+            code: Css::string(format!(
+                "padding: 0.2em 0.4em; margin: 0; font-size: 85%; white-space: break-spaces; \
+                 background-color: {}; border-radius: 6px; font-family: {};",
+                palette.code_bg, MONO_FONT
+            )),
+            codeblock: Css::string(format!(
+                "margin: 0 0 16px; padding: 16px; overflow: auto; font-size: 85%; line-height: 1.45; \
+                 color: {}; background-color: {}; border-radius: 6px;",
+                palette.fg, palette.pre_bg
+            )),
+            codeblock_code: Css::string(format!(
+                "font-family: {}; background: transparent;",
+                MONO_FONT
+            )),
+            code_figure: Css::str("margin: 0 0 16px;"),
+            code_line: Css::default(),
+            code_line_number: Css::string(format!(
+                "display: inline-block; min-width: 2em; margin-right: 1em; text-align: right; \
+                 color: {}; user-select: none;",
+                palette.muted
+            )),
+            code_title: Css::string(format!(
+                "padding: 8px 0; font-size: 85%; font-weight: 600; color: {};",
+                palette.muted
+            )),
+            container: Css::string(format!(
+                "font-family: {}; font-size: 16px; line-height: 1.5; color: {}; \
+                 background-color: {}; word-wrap: break-word;",
+                SANS_FONT, palette.fg, palette.bg
+            )),
+            dd: Css::str("margin: 0 0 16px; padding: 0 16px;"),
             del: Css::default(),
-            dl: Css::default(),
-            dt: Css::default(),
+            dl: Css::str("margin: 0 0 16px; padding: 0;"),
+            dt: Css::str("margin-top: 16px; font-weight: 600; font-style: italic;"),
             em: Css::default(),
-            h1: Css::default(),
-            h2: Css::default(),
-            h3: Css::default(),
-            h4: Css::default(),
-            h5: Css::default(),
-            h6: Css::default(),
-            img: Css::default(),
-            li: Css::default(),
-            a: Css::default(),
-            ol: Css::default(),
-            p: Css::default(),
-            strong: Css::default(),
+            footnote_backref: Css::string(format!(
+                "margin-left: 0.25em; color: {}; text-decoration: none;",
+                palette.link
+            )),
+            footnote_definition: Css::default(),
+            footnote_label: Css::string(format!(
+                "color: {}; text-decoration: none;",
+                palette.link
+            )),
+            footnote_reference: Css::str("font-size: 75%;"),
+            footnotes: Css::string(format!(
+                "margin-top: 16px; font-size: 12px; color: {}; border-top: 1px solid {};",
+                palette.muted, palette.border
+            )),
+            footnotes_list: Css::str("margin: 16px 0 0; padding-left: 2em;"),
+            h1: heading("2em", true),
+            h2: heading("1.5em", true),
+            h3: heading("1.25em", false),
+            h4: heading("1em", false),
+            h5: heading("0.875em", false),
+            h6: heading("0.85em", false).extend(Css::string(format!("color: {};", palette.muted))),
+            heading_anchor: Css::string(format!(
+                "margin-left: 0.25em; color: {}; text-decoration: none;",
+                palette.muted
+            )),
+            hr: Css::string(format!(
+                "height: 0.25em; padding: 0; margin: 24px 0; background-color: {}; border: 0;",
+                palette.border
+            )),
+            img: Css::str("max-width: 100%; box-sizing: content-box;"),
+            input: Css::str("margin: 0 0.2em 0.25em -1.4em; vertical-align: middle;"),
+            li: Css::str("margin-top: 0.25em;"),
+            li_depths: vec![],
+            math_display: Css::str("display: block; overflow: auto; text-align: center;"),
+            math_inline: Css::default(),
+            ol: list.clone(),
+            ol_depths: vec![
+                Css::str("list-style-type: decimal;"),
+                Css::str("list-style-type: lower-roman; margin-bottom: 0;"),
+                Css::str("list-style-type: lower-alpha; margin-bottom: 0;"),
+            ],
+            p: Css::str("margin: 0 0 16px;"),
+            strong: Css::str("font-weight: 600;"),
             sub: Css::default(),
             sup: Css::default(),
-            table: Css::str("border: 1"),
+            table: Css::str(
+                "display: block; width: max-content; max-width: 100%; overflow: auto; \
+                 margin: 0 0 16px; border-spacing: 0; border-collapse: collapse;",
+            ),
             tbody: Css::default(),
-            td: Css::default(),
-            th: Css::default(),
+            td: Css::string(cell.clone()),
+            th: Css::string(cell).extend(Css::str("font-weight: 600;")),
             thead: Css::default(),
-            tr: Css::default(),
-            ul: Css::default(),
+            toc: Css::default(),
+            tr: Css::string(format!(
+                "background-color: {}; border-top: 1px solid {};",
+                palette.bg, palette.border
+            )),
+            ul: list,
+            ul_depths: vec![
+                Css::str("list-style-type: disc;"),
+                Css::str("list-style-type: circle; margin-bottom: 0;"),
+                Css::str("list-style-type: square; margin-bottom: 0;"),
+            ],
         }
     }
 }
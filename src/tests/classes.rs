@@ -0,0 +1,112 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+};
+
+use crate::{to_vertigo_with_config, CMarkClasses, CMarkConfig, CMarkStyle, Options, TocConfig};
+
+fn config() -> CMarkConfig {
+    CMarkConfig {
+        classes: CMarkClasses::prefixed("md-"),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn prefixed() {
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "# Title\n\nLorem *ipsum*\n\n| a |\n|---|\n| 1 |",
+        Options::ENABLE_TABLES,
+        CMarkStyle::default(),
+        config(),
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div class="md-container">
+            <h1 class="md-h1">"Title"</h1>
+            <p class="md-p">"Lorem "<em class="md-em">"ipsum"</em></p>
+            <table class="md-table">
+                <thead class="md-thead"><tr class="md-tr"><th class="md-th">"a"</th></tr></thead>
+                <tbody class="md-tbody"><tr class="md-tr"><td class="md-td">"1"</td></tr></tbody>
+            </table>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn joined_with_own_classes() {
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "> [!NOTE]\n> Lorem\n\nIpsum $x$",
+        Options::ENABLE_GFM | Options::ENABLE_MATH,
+        CMarkStyle::default(),
+        CMarkConfig {
+            classes: CMarkClasses {
+                blockquote: "quote".to_string(),
+                alert_note: "note".to_string(),
+                math_inline: "formula".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <blockquote class="markdown-alert-note quote note"><p>"Lorem"</p></blockquote>
+            <p>"Ipsum "<span class="math math-inline formula">"x"</span></p>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn toc_and_footnotes() {
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "[TOC]\n\n# Title[^1]\n\n[^1]: Note",
+        Options::ENABLE_FOOTNOTES,
+        CMarkStyle::default(),
+        CMarkConfig {
+            toc: Some(TocConfig::default()),
+            ..config()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div class="md-container">
+            <ul class="toc md-ul md-toc">
+                <li class="md-li"><a href="#title" class="md-a">"Title"</a></li>
+            </ul>
+            <h1 class="md-h1" id="title">
+                "Title"
+                <sup class="footnote-reference md-footnote-reference">
+                    <a href="#fn-1" id="fnref-1" class="md-footnote-label">"1"</a>
+                </sup>
+            </h1>
+            <section class="footnotes md-footnotes">
+                <ol class="md-footnotes-list">
+                    <li id="fn-1" class="md-footnote-definition">
+                        <p class="md-p">"Note"</p>
+                        <a href="#fnref-1" class="footnote-backref md-footnote-backref">"↩"</a>
+                    </li>
+                </ol>
+            </section>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
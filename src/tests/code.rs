@@ -3,7 +3,7 @@ use vertigo::{
     inspect::{log_start, DomDebugFragment},
 };
 
-use crate::to_vertigo;
+use crate::{to_vertigo, to_vertigo_styled, CMarkStyle, CodeInfo};
 
 #[test]
 fn codeblock() {
@@ -37,3 +37,129 @@ let y = x + 1;
 
     assert_eq!(el1_str, el2_str);
 }
+
+#[test]
+fn codeblock_indented() {
+    log_start();
+    let _el1 = to_vertigo(
+        r#"
+Indented code:
+
+    let x = 2;
+    let y = x + 1;
+"#,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>"Indented code:"</p>
+            <pre>
+                <code>
+"let x = 2;
+let y = x + 1;
+"
+                </code>
+            </pre>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn codeblock_lines() {
+    log_start();
+    let _el1 = to_vertigo(
+        r#"
+```rust {2} linenos title="main.rs"
+let x = 2;
+let y = x + 1;
+```
+"#,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <figure class="code-block">
+                <figcaption class="code-title">"main.rs"</figcaption>
+                <pre>
+                    <code class="language-rust">
+                        <span class="line">
+                            <span class="line-number">"1"</span>
+                            "let x = 2;\n"
+                        </span>
+                        <span class="line highlighted">
+                            <span class="line-number">"2"</span>
+                            "let y = x + 1;\n"
+                        </span>
+                    </code>
+                </pre>
+            </figure>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn code_info() {
+    let info = CodeInfo::parse(r#"rust {1,3-5} linenos title="src/main.rs" ignore data-x='a b'"#);
+
+    assert_eq!(
+        info,
+        CodeInfo {
+            lang: "rust".to_string(),
+            highlight_lines: vec![1..=1, 3..=5],
+            line_numbers: true,
+            title: Some("src/main.rs".to_string()),
+            attrs: vec![
+                ("ignore".to_string(), String::new()),
+                ("data-x".to_string(), "a b".to_string()),
+            ],
+        }
+    );
+    assert!(info.is_line_highlighted(4));
+    assert!(!info.is_line_highlighted(2));
+
+    assert_eq!(CodeInfo::parse("{2}").lang, "");
+}
+
+#[test]
+fn line_styles() {
+    const LINE_STYLE: &str = "display: block";
+    const NUMBER_STYLE: &str = "color: gray";
+
+    log_start();
+    let _el1 = to_vertigo_styled(
+        "```rust linenos\nlet x = 2;\n```",
+        CMarkStyle {
+            code_line: vertigo::Css::str(LINE_STYLE),
+            code_line_number: vertigo::Css::str(NUMBER_STYLE),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <pre>
+                <code class="language-rust">
+                    <span class="line" style={LINE_STYLE}>
+                        <span class="line-number" style={NUMBER_STYLE}>"1"</span>
+                        "let x = 2;\n"
+                    </span>
+                </code>
+            </pre>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
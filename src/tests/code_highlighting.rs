@@ -1,9 +1,13 @@
+use std::sync::Arc;
 use vertigo::{
     dom,
     inspect::{log_start, DomDebugFragment},
 };
 
-use crate::to_vertigo;
+use crate::{
+    to_vertigo, to_vertigo_with_config, CMarkConfig, CMarkStyle, ClassStyle, CodeTheme,
+    HighlightMode, HighlighterContext, Options,
+};
 
 #[test]
 fn codeblock() {
@@ -26,40 +30,21 @@ if x == 4 {
     let _el2 = dom! {
         <div>
             <p>"Example of rust code:"</p>
-            <pre>
-                <span style="color: rgba(204, 153, 204, 255); background_color: rgba(45, 45, 45, 255)">"let"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">" x "</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"="</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">" "</span>
-                <span style="color: rgba(249, 145, 87, 255); background_color: rgba(45, 45, 45, 255)">"2"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">" "</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"+"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">" "</span>
-                <span style="color: rgba(249, 145, 87, 255); background_color: rgba(45, 45, 45, 255)">"2"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">";"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"\n"</span>
-                <span style="color: rgba(204, 153, 204, 255); background_color: rgba(45, 45, 45, 255)">"if"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">" x "</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"="</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"="</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">" "</span>
-                <span style="color: rgba(249, 145, 87, 255); background_color: rgba(45, 45, 45, 255)">"4"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">" "</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"{"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"\n"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"    "</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"println!"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"("</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"\""</span>
-                <span style="color: rgba(249, 145, 87, 255); background_color: rgba(45, 45, 45, 255)">"{}"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"\""</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">","</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">" x"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">")"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">";"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"\n"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"}"</span>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"\n"</span>
+            <pre style="background-color: rgba(45, 45, 45, 1); color: rgba(211, 208, 200, 1)">
+                <code class="language-rust">
+                    <span style="color: rgba(204, 153, 204, 1)">"let"</span>
+                    <span style="color: rgba(211, 208, 200, 1)">" x = "</span>
+                    <span style="color: rgba(249, 145, 87, 1)">"2"</span>
+                    <span style="color: rgba(211, 208, 200, 1)">" + "</span>
+                    <span style="color: rgba(249, 145, 87, 1)">"2"</span>
+                    <span style="color: rgba(211, 208, 200, 1)">";\n"</span>
+                    <span style="color: rgba(204, 153, 204, 1)">"if"</span>
+                    <span style="color: rgba(211, 208, 200, 1)">" x == "</span>
+                    <span style="color: rgba(249, 145, 87, 1)">"4"</span>
+                    <span style="color: rgba(211, 208, 200, 1)">" {\n    println!(\""</span>
+                    <span style="color: rgba(249, 145, 87, 1)">"{}"</span>
+                    <span style="color: rgba(211, 208, 200, 1)">"\", x);\n}\n"</span>
+                </code>
             </pre>
         </div>
     };
@@ -85,8 +70,180 @@ example
     let _el2 = dom! {
         <div>
             <p>"Example of unknown code:"</p>
-            <pre>
-                <span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"example\n"</span>
+            <pre style="background-color: rgba(45, 45, 45, 1); color: rgba(211, 208, 200, 1)">
+                <code>
+                    <span style="color: rgba(211, 208, 200, 1)">"example\n"</span>
+                </code>
+            </pre>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn codeblock_indented() {
+    log_start();
+    let _el1 = to_vertigo(
+        r#"
+Indented code:
+
+    example
+"#,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>"Indented code:"</p>
+            <pre style="background-color: rgba(45, 45, 45, 1); color: rgba(211, 208, 200, 1)">
+                <code>
+                    <span style="color: rgba(211, 208, 200, 1)">"example\n"</span>
+                </code>
+            </pre>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn codeblock_lines() {
+    log_start();
+    let _el1 = to_vertigo(
+        r#"
+```rust {2} linenos
+let x = 2;
+let y = 3;
+```
+"#,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <pre style="background-color: rgba(45, 45, 45, 1); color: rgba(211, 208, 200, 1)">
+                <code class="language-rust">
+                    <span class="line">
+                        <span class="line-number">"1"</span>
+                        <span style="color: rgba(204, 153, 204, 1)">"let"</span>
+                        <span style="color: rgba(211, 208, 200, 1)">" x = "</span>
+                        <span style="color: rgba(249, 145, 87, 1)">"2"</span>
+                        <span style="color: rgba(211, 208, 200, 1)">";\n"</span>
+                    </span>
+                    <span class="line highlighted">
+                        <span class="line-number">"2"</span>
+                        <span style="color: rgba(204, 153, 204, 1)">"let"</span>
+                        <span style="color: rgba(211, 208, 200, 1)">" y = "</span>
+                        <span style="color: rgba(249, 145, 87, 1)">"3"</span>
+                        <span style="color: rgba(211, 208, 200, 1)">";\n"</span>
+                    </span>
+                </code>
+            </pre>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn preloaded_context() {
+    let config = CMarkConfig {
+        highlighter: Some(Arc::new(HighlighterContext::load_defaults())),
+        ..Default::default()
+    };
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "```\nexample\n```",
+        Options::empty(),
+        CMarkStyle::default(),
+        config,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <pre style="background-color: rgba(45, 45, 45, 1); color: rgba(211, 208, 200, 1)">
+                <code>
+                    <span style="color: rgba(211, 208, 200, 1)">"example\n"</span>
+                </code>
+            </pre>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn missing_theme_fallback() {
+    let config = CMarkConfig {
+        theme: CodeTheme::named("no such theme"),
+        ..Default::default()
+    };
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "```\nexample\n```",
+        Options::empty(),
+        CMarkStyle::default(),
+        config,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <pre style="background-color: rgba(45, 45, 45, 1); color: rgba(211, 208, 200, 1)">
+                <code>
+                    <span style="color: rgba(211, 208, 200, 1)">"example\n"</span>
+                </code>
+            </pre>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn named_theme() {
+    let ctx = HighlighterContext::shared();
+    let theme = &ctx.theme_set.themes["InspiredGitHub"];
+    let background = theme.settings.background.unwrap();
+    let foreground = theme.settings.foreground.unwrap();
+    let config = CMarkConfig {
+        theme: CodeTheme::named("InspiredGitHub"),
+        ..Default::default()
+    };
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "```\nexample\n```",
+        Options::empty(),
+        CMarkStyle::default(),
+        config,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    let foreground = crate::highlighting::color_to_css(&foreground);
+    let background = crate::highlighting::color_to_css(&background);
+    let pre_style = format!("background-color: {background}; color: {foreground}");
+    let span_style = format!("color: {foreground}");
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <pre style={pre_style}>
+                <code>
+                    <span style={span_style}>"example\n"</span>
+                </code>
             </pre>
         </div>
     };
@@ -94,3 +251,74 @@ example
 
     assert_eq!(el1_str, el2_str);
 }
+
+#[test]
+fn classes_mode() {
+    let config = CMarkConfig {
+        highlight_mode: HighlightMode::Classes(ClassStyle::SpacedPrefixed { prefix: "hl-" }),
+        ..Default::default()
+    };
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "```\nexample\n```",
+        Options::empty(),
+        CMarkStyle::default(),
+        config,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <pre class="hl-code">
+                <code>
+                    <span class="hl-text hl-plain">"example\n"</span>
+                </code>
+            </pre>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn classes_stylesheet() {
+    let css = CodeTheme::default()
+        .stylesheet(
+            &HighlighterContext::shared(),
+            ClassStyle::SpacedPrefixed { prefix: "hl-" },
+        )
+        .unwrap();
+
+    assert!(css.contains(".hl-code {"));
+}
+
+#[test]
+fn preset_theme_from_config() {
+    const SOURCE: &str = "```rust\nlet x = 2;\n```";
+    const DEFAULT_BACKGROUND: &str = "background-color: rgba(45, 45, 45, 1)";
+
+    // Style preset doesn't override theme set in config
+    log_start();
+    let _el = to_vertigo_with_config(
+        SOURCE,
+        Options::empty(),
+        CMarkStyle::github(),
+        CMarkConfig::default(),
+    );
+    let html = DomDebugFragment::from_log().to_pseudo_html();
+    assert!(html.contains(DEFAULT_BACKGROUND));
+
+    log_start();
+    let _el = to_vertigo_with_config(
+        SOURCE,
+        Options::empty(),
+        CMarkStyle::github(),
+        CMarkConfig::github(),
+    );
+    let html = DomDebugFragment::from_log().to_pseudo_html();
+    assert!(!html.contains(DEFAULT_BACKGROUND));
+    assert!(html.contains("background-color: rgba(255, 255, 255, 1)"));
+}
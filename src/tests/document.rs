@@ -0,0 +1,95 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+};
+
+use crate::{
+    CMarkConfig, CMarkStyle, CowStr, DemoteHeadings, Document, DocumentNode, Event, HeadingLevel,
+    Options, RewriteLinks, Tag,
+};
+
+#[test]
+fn tree_with_spans() {
+    let document = Document::parse("# Foo\n\nBar *baz*", Options::empty());
+    let heading = Tag::Heading {
+        level: HeadingLevel::H1,
+        id: None,
+        classes: vec![],
+        attrs: vec![],
+    };
+    let text = |text: &'static str, span| DocumentNode::Leaf {
+        event: Event::Text(CowStr::Borrowed(text)),
+        span,
+    };
+    assert_eq!(
+        document.nodes,
+        vec![
+            DocumentNode::Element {
+                tag: heading,
+                children: vec![text("Foo", 2..5)],
+                span: 0..6,
+            },
+            DocumentNode::Element {
+                tag: Tag::Paragraph,
+                children: vec![
+                    text("Bar ", 7..11),
+                    DocumentNode::Element {
+                        tag: Tag::Emphasis,
+                        children: vec![text("baz", 12..15)],
+                        span: 11..16,
+                    }
+                ],
+                span: 7..16,
+            },
+        ]
+    );
+}
+
+#[test]
+fn events_round_trip() {
+    let source = "- [x] Foo[^a]\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n[^a]: Bar";
+    let opts = Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS | Options::ENABLE_FOOTNOTES;
+    let document = Document::parse(source, opts);
+    let events = pulldown_cmark::Parser::new_ext(source, opts)
+        .map(Event::into_static)
+        .collect::<Vec<_>>();
+    assert_eq!(document.events(), events);
+}
+
+#[test]
+fn transforms() {
+    let mut document = Document::parse(
+        "# Foo\n\n###### Bar\n\n[Baz](/baz) ![img](img.png)",
+        Options::empty(),
+    );
+    document
+        .apply(DemoteHeadings(2))
+        .apply(RewriteLinks(|url: &str| match url.strip_prefix('/') {
+            Some(path) => format!("https://example.com/{}", path),
+            None => url.to_string(),
+        }))
+        .apply(|document: &mut Document| {
+            document.nodes.remove(1);
+        });
+
+    log_start();
+    let _el = document.to_vertigo(CMarkStyle::default(), CMarkConfig::default());
+    let el_str = DomDebugFragment::from_log().to_pseudo_html();
+    log_start();
+    let _el = dom! {
+        <div>
+            <h3>"Foo"</h3>
+            <p>
+                <a href="https://example.com/baz">"Baz"</a>
+                " "
+                <img src="img.png" alt="img" />
+            </p>
+        </div>
+    };
+    assert_eq!(el_str, DomDebugFragment::from_log().to_pseudo_html());
+
+    assert_eq!(
+        document.to_plain_text(None),
+        "Foo\n\nBaz (https://example.com/baz) img"
+    );
+}
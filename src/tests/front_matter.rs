@@ -0,0 +1,136 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+};
+
+use crate::{
+    to_vertigo_with_front_matter, CMarkConfig, CMarkStyle, FrontMatter, MetadataBlockKind, Options,
+    TocConfig,
+};
+
+#[test]
+fn yaml_front_matter() {
+    log_start();
+    let (_el1, front_matter) = to_vertigo_with_front_matter(
+        "---\ntitle: Hello\ntags: [a, b]\n---\n\n# Hello\n\nText",
+        Options::empty(),
+        CMarkStyle::default(),
+        CMarkConfig::default(),
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <h1>"Hello"</h1>
+            <p>"Text"</p>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+    assert_eq!(
+        front_matter,
+        Some(FrontMatter {
+            kind: MetadataBlockKind::YamlStyle,
+            raw: "title: Hello\ntags: [a, b]\n".to_string(),
+        })
+    );
+}
+
+#[test]
+fn front_matter_with_toc() {
+    log_start();
+    let (_el1, front_matter) = to_vertigo_with_front_matter(
+        "---\ntitle: Hello\n---\n\n[TOC]\n\n# Hello\n\n## World",
+        Options::empty(),
+        CMarkStyle::default(),
+        CMarkConfig {
+            toc: Some(TocConfig::default()),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <ul class="toc">
+                <li>
+                    <a href="#hello">"Hello"</a>
+                    <ul>
+                        <li><a href="#world">"World"</a></li>
+                    </ul>
+                </li>
+            </ul>
+            <h1 id="hello">"Hello"</h1>
+            <h2 id="world">"World"</h2>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+    assert_eq!(front_matter.unwrap().raw, "title: Hello\n");
+}
+
+#[test]
+fn toml_front_matter() {
+    let (_el, front_matter) = to_vertigo_with_front_matter(
+        "+++\ntitle = \"Hello\"\n+++\n\nText",
+        Options::empty(),
+        CMarkStyle::default(),
+        CMarkConfig::default(),
+    );
+
+    assert_eq!(
+        front_matter,
+        Some(FrontMatter {
+            kind: MetadataBlockKind::PlusesStyle,
+            raw: "title = \"Hello\"\n".to_string(),
+        })
+    );
+}
+
+#[test]
+fn no_front_matter() {
+    let (_el, front_matter) = to_vertigo_with_front_matter(
+        "Text\n\n---\n\nMore",
+        Options::empty(),
+        CMarkStyle::default(),
+        CMarkConfig::default(),
+    );
+
+    assert_eq!(front_matter, None);
+}
+
+#[cfg(feature = "front-matter")]
+#[test]
+fn front_matter_map() {
+    use crate::MetaValue;
+
+    let yaml = FrontMatter {
+        kind: MetadataBlockKind::YamlStyle,
+        raw: "title: \"Hello: world\"\ndate: 2024-01-02\ntags:\n  - rust\n  - web\nauthor:\n  name: Someone\n".to_string(),
+    };
+    let map = yaml.to_map();
+    assert_eq!(map["title"], MetaValue::Text("Hello: world".to_string()));
+    assert_eq!(map["date"], MetaValue::Text("2024-01-02".to_string()));
+    assert_eq!(
+        map["tags"],
+        MetaValue::List(vec!["rust".to_string(), "web".to_string()])
+    );
+    assert!(!map.contains_key("author"));
+
+    let toml = FrontMatter {
+        kind: MetadataBlockKind::PlusesStyle,
+        raw: "title = 'Hello'\ndraft = true\ntags = [\"rust\", \"web\"]\n\n[extra]\nkey = 1\n"
+            .to_string(),
+    };
+    let map = toml.to_map();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["draft"], MetaValue::Text("true".to_string()));
+    assert_eq!(
+        map["tags"],
+        MetaValue::List(vec!["rust".to_string(), "web".to_string()])
+    );
+}
@@ -0,0 +1,104 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+    Value,
+};
+
+use super::reactive::strip_markers;
+use crate::{to_vertigo_with_config, CMarkConfig, CMarkStyle, Markdown, Options};
+
+const HEADINGS: &str = r#"
+# Hello, World!
+
+## Setup `cargo` & tools
+
+## Hello World
+
+# Hello, world
+
+## Custom {#custom}
+"#;
+
+#[test]
+fn generated_ids() {
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        HEADINGS,
+        Options::ENABLE_HEADING_ATTRIBUTES,
+        CMarkStyle::default(),
+        CMarkConfig {
+            heading_ids: true,
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <h1 id="hello-world">"Hello, World!"</h1>
+            <h2 id="setup-cargo--tools">"Setup "<code>"cargo"</code>" & tools"</h2>
+            <h2 id="hello-world-1">"Hello World"</h2>
+            <h1 id="hello-world-2">"Hello, world"</h1>
+            <h2 id="custom">"Custom"</h2>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn permalinks() {
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "# Title\n\n## Custom {#custom}",
+        Options::ENABLE_HEADING_ATTRIBUTES,
+        CMarkStyle::default(),
+        CMarkConfig {
+            heading_ids: true,
+            heading_permalink: Some("#".to_string()),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <h1 id="title">"Title"<a class="heading-anchor" href="#title">"#"</a></h1>
+            <h2 id="custom">"Custom"<a class="heading-anchor" href="#custom">"#"</a></h2>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn generated_ids_in_blocks() {
+    let config = CMarkConfig {
+        heading_ids: true,
+        ..Default::default()
+    };
+
+    log_start();
+    let _el1 = Markdown {
+        opts: Options::ENABLE_HEADING_ATTRIBUTES,
+        config: config.clone(),
+        ..Markdown::new(Value::new(HEADINGS.to_string()).to_computed())
+    }
+    .mount();
+    let el1_str = strip_markers(DomDebugFragment::from_log().to_pseudo_html());
+
+    log_start();
+    let _el2 = to_vertigo_with_config(
+        HEADINGS,
+        Options::ENABLE_HEADING_ATTRIBUTES,
+        CMarkStyle::default(),
+        config,
+    );
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
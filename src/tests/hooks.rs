@@ -0,0 +1,107 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+    DomElement,
+};
+
+use crate::{
+    to_vertigo_with_config, BlockQuoteKind, CMarkConfig, CMarkStyle, ElementTag, HeadingLevel,
+    Options,
+};
+
+#[test]
+fn hooks() {
+    let config = CMarkConfig::default().with_hooks(|tag: &ElementTag| match tag {
+        ElementTag::Heading {
+            level: HeadingLevel::H2,
+            ..
+        } => Some(DomElement::new("h2").attr("class", "title")),
+        ElementTag::Link { url, title, .. } => Some(
+            DomElement::new("a")
+                .attr("href", format!("/app{url}"))
+                .attr("data-title", *title),
+        ),
+        ElementTag::Image { url, alt, .. } => Some(
+            DomElement::new("figure")
+                .child(DomElement::new("img").attr("src", *url))
+                .child(DomElement::new("figcaption").child_text(*alt)),
+        ),
+        ElementTag::BlockQuote {
+            kind: Some(BlockQuoteKind::Tip),
+        } => Some(DomElement::new("aside")),
+        _ => None,
+    });
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        r#"
+# Heading
+
+## Subheading
+
+[Docs](/docs "Documentation") ![A cat](cat.png)
+
+> [!TIP]
+> Use hooks
+"#,
+        Options::ENABLE_GFM,
+        CMarkStyle::default(),
+        config,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <h1>"Heading"</h1>
+            <h2 class="title">"Subheading"</h2>
+            <p>
+                <a href="/app/docs" data-title="Documentation">"Docs"</a>" "
+                <figure><img src="cat.png" /><figcaption>"A cat"</figcaption></figure>
+            </p>
+            <aside><p>"Use hooks"</p></aside>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn table_hooks() {
+    let config = CMarkConfig::default().with_hooks(|tag: &ElementTag| match tag {
+        ElementTag::Table { alignments } => {
+            Some(DomElement::new("table").attr("data-columns", alignments.len().to_string()))
+        }
+        ElementTag::TableCell { head: true, .. } => {
+            Some(DomElement::new("th").attr("scope", "col"))
+        }
+        _ => None,
+    });
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "| a | b |\n|---|--:|\n| 1 | 2 |",
+        Options::ENABLE_TABLES,
+        CMarkStyle::default(),
+        config,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <table data-columns="2">
+                <thead>
+                    <tr><th scope="col">"a"</th><th scope="col">"b"</th></tr>
+                </thead>
+                <tbody>
+                    <tr><td>"1"</td><td style="text-align: right">"2"</td></tr>
+                </tbody>
+            </table>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
@@ -0,0 +1,159 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+};
+
+use crate::{to_vertigo, to_vertigo_with_config, CMarkConfig, CMarkStyle, HtmlPolicy, Options};
+
+#[test]
+fn inline_html() {
+    log_start();
+    let _el1 = to_vertigo("Press <kbd>Ctrl</kbd> + <kbd>C</kbd><br>E = mc<sup>2</sup>");
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>
+                "Press "<kbd>"Ctrl"</kbd>" + "<kbd>"C"</kbd><br />
+                "E = mc"<sup>"2"</sup>
+            </p>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn html_block_with_markdown_inside() {
+    log_start();
+    let _el1 = to_vertigo(
+        r#"
+<details open>
+<summary>More &amp; more</summary>
+
+Hidden *text*
+</details>
+"#,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <details open="">
+                <summary>"More & more"</summary>
+                <p>"Hidden "<em>"text"</em></p>
+            </details>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn unclosed_inline_html() {
+    log_start();
+    let _el1 = to_vertigo("Some <b>bold\n\nNext paragraph");
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>"Some "<b>"bold"</b></p>
+            <p>"Next paragraph"</p>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn sanitizing() {
+    log_start();
+    let _el1 = to_vertigo(
+        r#"
+<script>alert("script")</script>
+
+<a href="javascript:alert(1)" onclick="alert(2)" title="Title">Evil</a>
+<a href="https://example.com">Good</a> <font color="red">text</font>
+
+[Link](javascript:alert(3)) ![Image](javascript:alert(4)) <JavaScript:alert(5)>
+"#,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>
+                <a title="Title">"Evil"</a>" "
+                <a href="https://example.com">"Good"</a>" text"
+            </p>
+            <p>
+                <a>"Link"</a>" "
+                <img alt="Image" />" "
+                <a>"JavaScript:alert(5)"</a>
+            </p>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn inline_raw_text_elements() {
+    log_start();
+    let _el1 =
+        to_vertigo("Foo <style>body{display:none}</style> bar <script>x(*y*, `z`)</script> baz");
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! { <div><p>"Foo "" bar "" baz"</p></div> };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn unclosed_inline_script() {
+    log_start();
+    let _el1 = to_vertigo("Foo <script>x()\n\nBar <kbd>Ctrl</kbd>");
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>"Foo "</p>
+            <p>"Bar "<kbd>"Ctrl"</kbd></p>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn custom_policy() {
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "<kbd>Ctrl</kbd> <mark>marked</mark>",
+        Options::empty(),
+        CMarkStyle::default(),
+        CMarkConfig {
+            html: HtmlPolicy::deny_all().allow_tag("mark", &[]),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! { <div><p>"Ctrl "<mark>"marked"</mark></p></div> };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
@@ -0,0 +1,76 @@
+use vertigo::Css;
+
+use crate::{to_html_string, CMarkConfig, CMarkStyle, Options};
+
+fn html(text: &str, opts: Options) -> String {
+    to_html_string(text, opts, CMarkStyle::default(), CMarkConfig::default())
+}
+
+#[test]
+fn escaping() {
+    assert_eq!(
+        html("[a & \"b\"](/x?a=1&b=\"2\") <3", Options::empty()),
+        "<div><p><a href=\"/x?a=1&amp;b=&quot;2&quot;\">a &amp; \"b\"</a> &lt;3</p></div>"
+    );
+}
+
+#[test]
+fn styles() {
+    let style = CMarkStyle {
+        p: Css::str("margin: 0;"),
+        em: Css::str("color: red"),
+        ..Default::default()
+    };
+    assert_eq!(
+        to_html_string("*x*", Options::empty(), style, CMarkConfig::default()),
+        "<div><p style=\"margin: 0\"><em style=\"color: red\">x</em></p></div>"
+    );
+}
+
+#[test]
+fn table() {
+    assert_eq!(
+        html("| a | b |\n|:--|--:|\n| 1 | 2 |", Options::ENABLE_TABLES),
+        concat!(
+            "<div><table><thead><tr>",
+            "<th style=\"text-align: left\">a</th><th style=\"text-align: right\">b</th>",
+            "</tr></thead><tbody><tr>",
+            "<td style=\"text-align: left\">1</td><td style=\"text-align: right\">2</td>",
+            "</tr></tbody></table></div>",
+        )
+    );
+}
+
+#[test]
+fn task_list() {
+    assert_eq!(
+        html("- [x] done\n- [ ] todo", Options::ENABLE_TASKLISTS),
+        concat!(
+            "<div><ul>",
+            "<li><input disabled=\"\" type=\"checkbox\" checked=\"checked\">done</li>",
+            "<li><input disabled=\"\" type=\"checkbox\">todo</li>",
+            "</ul></div>",
+        )
+    );
+}
+
+#[test]
+fn footnotes() {
+    assert_eq!(
+        html("Foo[^a]\n\n[^a]: Bar", Options::ENABLE_FOOTNOTES),
+        concat!(
+            "<div><p>Foo<sup class=\"footnote-reference\">",
+            "<a href=\"#fn-a\" id=\"fnref-a\">1</a></sup></p>",
+            "<section class=\"footnotes\"><ol><li id=\"fn-a\"><p>Bar</p>",
+            "<a href=\"#fnref-a\" class=\"footnote-backref\">↩</a></li></ol></section></div>",
+        )
+    );
+}
+
+#[test]
+fn alert() {
+    assert_eq!(
+        html("> [!NOTE]\n> Hi", Options::ENABLE_GFM),
+        "<div><blockquote class=\"markdown-alert-note\"><p>Hi</p></blockquote></div>"
+    );
+}
@@ -1,9 +1,17 @@
+use pulldown_cmark::Parser;
+use std::{cell::RefCell, rc::Rc};
 use vertigo::{
     dom,
     inspect::{log_start, DomDebugFragment},
+    Value,
 };
 
-use crate::{to_vertigo, to_vertigo_opts, Options};
+use super::reactive::strip_markers;
+
+use crate::{
+    generate::generate_node, source_block_at, to_html_string, to_vertigo, to_vertigo_opts,
+    to_vertigo_with_config, toggle_task, CMarkConfig, CMarkStyle, Markdown, Options,
+};
 
 #[test]
 fn lists() {
@@ -127,3 +135,126 @@ Implement in vertigo-cmark:
 
     assert_eq!(el1_str, el2_str);
 }
+
+/// Removes ids of click handlers which differ between trees
+fn without_handlers(html: String) -> String {
+    let mut parts = html.split(" click=");
+    let mut result = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        result.push_str(part.trim_start_matches(|c: char| c.is_ascii_digit()));
+    }
+    result
+}
+
+#[test]
+fn interactive_task_list() {
+    let config = CMarkConfig::default().with_task_toggle(|_, _| {});
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "- [x] foo\n- [ ] bar",
+        Options::ENABLE_TASKLISTS,
+        CMarkStyle::default(),
+        config.clone(),
+    );
+    let el1_str = without_handlers(DomDebugFragment::from_log().to_pseudo_html());
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <ul>
+                <li>
+                    <input checked="checked" type="checkbox" on_click={|_| {}} />
+                    "foo"
+                </li>
+                <li>
+                    <input type="checkbox" on_click={|_| {}} />
+                    "bar"
+                </li>
+            </ul>
+        </div>
+    };
+    let el2_str = without_handlers(DomDebugFragment::from_log().to_pseudo_html());
+
+    assert_eq!(el1_str, el2_str);
+
+    // Checkboxes in HTML output can't be clicked
+    assert_eq!(
+        to_html_string(
+            "- [ ] foo",
+            Options::ENABLE_TASKLISTS,
+            CMarkStyle::default(),
+            config
+        ),
+        "<div><ul><li><input disabled=\"\" type=\"checkbox\">foo</li></ul></div>"
+    );
+}
+
+#[test]
+fn task_toggle_callback() {
+    let clicks = Rc::new(RefCell::new(vec![]));
+    let config = CMarkConfig::default().with_task_toggle({
+        let clicks = clicks.clone();
+        move |item, checked| clicks.borrow_mut().push((item, checked))
+    });
+
+    let source = "Intro\n\n- [x] foo\n- [ ] bar\n";
+    let parser = Parser::new_ext(source, Options::ENABLE_TASKLISTS).into_offset_iter();
+    generate_node(parser, CMarkStyle::default(), config).click_all();
+
+    assert_eq!(*clicks.borrow(), vec![(7..17, false), (17..27, true)]);
+}
+
+#[test]
+fn interactive_task_list_in_component() {
+    let text = "Intro\n\n- [x] foo\n- [ ] bar";
+    let source = Value::new("- [x] foo\n- [ ] bar".to_string());
+    let config = CMarkConfig::default().with_task_toggle(|_, _| {});
+
+    log_start();
+    let _el1 = Markdown {
+        opts: Options::ENABLE_TASKLISTS,
+        config: config.clone(),
+        ..Markdown::new(source.to_computed())
+    }
+    .mount();
+    source.set(text.to_string());
+    let el1_str = without_handlers(strip_markers(DomDebugFragment::from_log().to_pseudo_html()));
+
+    log_start();
+    let _el2 = to_vertigo_with_config(
+        text,
+        Options::ENABLE_TASKLISTS,
+        CMarkStyle::default(),
+        config,
+    );
+    let el2_str = without_handlers(DomDebugFragment::from_log().to_pseudo_html());
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn toggle_task_marker() {
+    let source = "- [ ] foo\n- [x] bar [ ]\n  - [X] baz\n- qux [ ]\n";
+    assert_eq!(
+        toggle_task(source, 0..10, true),
+        "- [x] foo\n- [x] bar [ ]\n  - [X] baz\n- qux [ ]\n"
+    );
+    assert_eq!(
+        toggle_task(source, 10..36, false),
+        "- [ ] foo\n- [ ] bar [ ]\n  - [X] baz\n- qux [ ]\n"
+    );
+    assert_eq!(
+        toggle_task(source, 26..36, false),
+        "- [ ] foo\n- [x] bar [ ]\n  - [ ] baz\n- qux [ ]\n"
+    );
+    // Span of the item as passed to the callback
+    let item = source_block_at(source, Options::ENABLE_TASKLISTS, 28).unwrap();
+    assert_eq!(
+        toggle_task(source, item, false),
+        "- [ ] foo\n- [x] bar [ ]\n  - [ ] baz\n- qux [ ]\n"
+    );
+    // Item without marker at the start
+    assert_eq!(toggle_task(source, 36..46, true), source);
+    assert_eq!(toggle_task(source, 40..80, true), source);
+}
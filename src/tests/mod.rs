@@ -5,15 +5,28 @@ use vertigo::{
 
 use crate::{to_vertigo, to_vertigo_opts};
 
+mod classes;
 #[cfg(not(feature = "syntect"))]
 mod code;
 
 #[cfg(feature = "syntect")]
 mod code_highlighting;
 
+mod document;
+
+mod front_matter;
+mod headings;
+mod hooks;
+mod html;
+mod html_string;
 mod lists;
+mod plain_text;
+mod reactive;
+mod source_positions;
+mod stream;
 mod styling;
 mod table;
+mod toc;
 
 #[test]
 fn text() {
@@ -223,19 +236,114 @@ This is sentence with footnote[^1]. I hope it works[^2].
         <div>
             <p>
                 "This is sentence with footnote"
-                <sup class="footnote-reference"><a href="#1">"1"</a></sup>
+                <sup class="footnote-reference"><a href="#fn-1" id="fnref-1">"1"</a></sup>
                 ". I hope it works"
-                <sup class="footnote-reference"><a href="#2">"2"</a></sup>
+                <sup class="footnote-reference"><a href="#fn-2" id="fnref-2">"2"</a></sup>
+                "."
+            </p>
+            <section class="footnotes">
+                <ol>
+                    <li id="fn-1">
+                        <p>"In fact, this is a footnote."</p>
+                        <a class="footnote-backref" href="#fnref-1">"↩"</a>
+                    </li>
+                    <li id="fn-2">
+                        <p>"Or at least the test passes."</p>
+                        <a class="footnote-backref" href="#fnref-2">"↩"</a>
+                    </li>
+                </ol>
+            </section>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn footnotes_numbered_by_references() {
+    let opts = super::Options::ENABLE_FOOTNOTES;
+
+    log_start();
+    let _el1 = to_vertigo_opts(
+        r#"
+[^a]: First defined.
+
+[^b]: Second defined.
+
+[^c]: Never referenced.
+
+Second[^b] and first[^a]."#,
+        opts,
+    );
+
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>
+                "Second"
+                <sup class="footnote-reference"><a href="#fn-b" id="fnref-b">"1"</a></sup>
+                " and first"
+                <sup class="footnote-reference"><a href="#fn-a" id="fnref-a">"2"</a></sup>
+                "."
+            </p>
+            <section class="footnotes">
+                <ol>
+                    <li id="fn-b">
+                        <p>"Second defined."</p>
+                        <a class="footnote-backref" href="#fnref-b">"↩"</a>
+                    </li>
+                    <li id="fn-a">
+                        <p>"First defined."</p>
+                        <a class="footnote-backref" href="#fnref-a">"↩"</a>
+                    </li>
+                </ol>
+            </section>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn footnote_back_references() {
+    let opts = super::Options::ENABLE_OLD_FOOTNOTES;
+
+    log_start();
+    let _el1 = to_vertigo_opts(
+        r#"
+[^note]: Defined first.
+
+One[^note], two[^note] and missing[^other]."#,
+        opts,
+    );
+
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>
+                "One"
+                <sup class="footnote-reference"><a href="#fn-note" id="fnref-note">"1"</a></sup>
+                ", two"
+                <sup class="footnote-reference"><a href="#fn-note" id="fnref-note-2">"1"</a></sup>
+                " and missing"
+                "[^other]"
                 "."
             </p>
-            <div class="footnote-definition" id="1">
-                <sup class="footnote-definition-label">"1"</sup>
-                <p>"In fact, this is a footnote."</p>
-            </div>
-            <div class="footnote-definition" id="2">
-                <sup class="footnote-definition-label">"2"</sup>
-                <p>"Or at least the test passes."</p>
-            </div>
+            <section class="footnotes">
+                <ol>
+                    <li id="fn-note">
+                        <p>"Defined first."</p>
+                        <a class="footnote-backref" href="#fnref-note">"↩"</a>
+                        <a class="footnote-backref" href="#fnref-note-2">"↩"<sup>"2"</sup></a>
+                    </li>
+                </ol>
+            </section>
         </div>
     };
     let el2_str = DomDebugFragment::from_log().to_pseudo_html();
@@ -396,3 +504,48 @@ fn image() {
 
     assert_eq!(el1_str, el2_str);
 }
+
+#[test]
+fn custom_code_renderer() {
+    use crate::{to_vertigo_with_config, CMarkConfig, CMarkStyle, CodeInfo, Options};
+    use vertigo::{DomElement, DomNode};
+
+    let config = CMarkConfig::default().with_code_renderer("csv", |_: &CodeInfo, source: &str| {
+        let table = DomElement::new("table");
+        for line in source.lines() {
+            let row = DomElement::new("tr");
+            for cell in line.split(',') {
+                row.add_child(DomElement::new("td").child_text(cell));
+            }
+            table.add_child(row);
+        }
+        DomNode::from(table)
+    });
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        r#"
+```csv title="data.csv"
+a,b
+1,2
+```
+"#,
+        Options::empty(),
+        CMarkStyle::default(),
+        config,
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <table>
+                <tr><td>"a"</td><td>"b"</td></tr>
+                <tr><td>"1"</td><td>"2"</td></tr>
+            </table>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
@@ -0,0 +1,75 @@
+use crate::{to_plain_text, Options};
+
+#[test]
+fn blocks() {
+    let text = to_plain_text(
+        "# Title\n\nLorem *ipsum*\ndolor  \nsit\n\n---\n\n> Quote\n>\n> > Nested\n\n```rust\nfn main() {\n    run();\n}\n```",
+        Options::empty(),
+        None,
+    );
+    assert_eq!(
+        text,
+        concat!(
+            "Title\n\nLorem ipsum dolor\nsit\n\n---\n\n> Quote\n>\n> > Nested\n\n",
+            "fn main() {\n    run();\n}"
+        )
+    );
+}
+
+#[test]
+fn lists() {
+    let text = to_plain_text(
+        "- foo\n- bar\n  1. baz\n  2. qux\n\n3. loose\n\n   continued\n4. last\n\n- [x] done\n- [ ] todo",
+        Options::ENABLE_TASKLISTS,
+        None,
+    );
+    assert_eq!(
+        text,
+        concat!(
+            "- foo\n- bar\n  1. baz\n  2. qux\n\n",
+            "3. loose\n\n   continued\n\n4. last\n\n",
+            "- [x] done\n- [ ] todo"
+        )
+    );
+}
+
+#[test]
+fn table() {
+    let text = to_plain_text(
+        "| a | b |\n|---|---|\n| 1 | 2 |\n| 3 |   |",
+        Options::ENABLE_TABLES,
+        None,
+    );
+    assert_eq!(text, "a\tb\n1\t2\n3\t");
+}
+
+#[test]
+fn links_and_footnotes() {
+    let text = to_plain_text(
+        "[Docs](https://docs.rs), <https://crates.io>, ![logo](logo.png)[^a] and <b>html</b>[^b]\n\n[^a]: Note with `code`",
+        Options::ENABLE_FOOTNOTES,
+        None,
+    );
+    assert_eq!(
+        text,
+        concat!(
+            "Docs (https://docs.rs), https://crates.io, logo[1] and html[^b]\n\n",
+            "[1] Note with code"
+        )
+    );
+}
+
+#[test]
+fn limit() {
+    let source = "Lorem ipsum dolor sit amet";
+    assert_eq!(to_plain_text(source, Options::empty(), Some(26)), source);
+    assert_eq!(
+        to_plain_text(source, Options::empty(), Some(12)),
+        "Lorem ipsum…"
+    );
+    assert_eq!(to_plain_text(source, Options::empty(), Some(11)), "Lorem…");
+    assert_eq!(
+        to_plain_text("Loremipsum", Options::empty(), Some(6)),
+        "Lorem…"
+    );
+}
@@ -0,0 +1,83 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+    Value,
+};
+
+use crate::{reactive::split_blocks, Markdown, Options};
+
+/// Removes comments used by Vertigo as list and value markers
+pub(super) fn strip_markers(html: String) -> String {
+    let mut output = String::new();
+    let mut rest = html.as_str();
+    while let Some(start) = rest.find("<!--") {
+        output.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("-->")
+            .map(|end| &rest[start + end + 3..])
+            .unwrap_or_default();
+    }
+    output.push_str(rest);
+    output
+}
+
+#[test]
+fn markdown_component() {
+    let source = Value::new("# Title\n\nSome *text*".to_string());
+
+    log_start();
+    let _el1 = Markdown::new(source.to_computed()).mount();
+    source.set("# Title\n\nOther *text*".to_string());
+    let el1_str = strip_markers(DomDebugFragment::from_log().to_pseudo_html());
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <h1>"Title"</h1>
+            <p>"Other "<em>"text"</em></p>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn blocks_keys() {
+    let keys = |text: &str| {
+        split_blocks(text, Options::ENABLE_FOOTNOTES, false)
+            .into_iter()
+            .map(|block| (block.source, block.occurrence))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        keys("Foo\n\n<details>\n\nBar[^1]\n</details>\n\nFoo\n\n[^1]: Baz"),
+        vec![
+            ("Foo\n".to_string(), 0),
+            ("<details>\n\nBar[^1]\n</details>\n".to_string(), 0),
+            ("Foo\n".to_string(), 1),
+            ("[^1]: Baz".to_string(), 0),
+        ]
+    );
+}
+
+#[test]
+fn footnotes_across_blocks() {
+    let text = "Foo[^a] bar[^b]\n\n[^b]: B\n\n[^a]: A\n\nBaz[^a]";
+    let source = Value::new(text.to_string());
+
+    log_start();
+    let _el1 = Markdown {
+        opts: Options::ENABLE_FOOTNOTES,
+        ..Markdown::new(source.to_computed())
+    }
+    .mount();
+    let el1_str = strip_markers(DomDebugFragment::from_log().to_pseudo_html());
+
+    log_start();
+    let _el2 = crate::to_vertigo_opts(text, Options::ENABLE_FOOTNOTES);
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
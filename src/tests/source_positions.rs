@@ -0,0 +1,107 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+    Value,
+};
+
+use super::reactive::strip_markers;
+
+use crate::{
+    source_block_at, to_html_string, to_vertigo_with_config, CMarkConfig, CMarkStyle, Markdown,
+    MarkdownStream, Options,
+};
+
+fn config() -> CMarkConfig {
+    CMarkConfig {
+        source_positions: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn blocks() {
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "# Foo\n\nBar *baz*\n\n---\n",
+        Options::empty(),
+        CMarkStyle::default(),
+        config(),
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <h1 data-source-start="0" data-source-end="6">"Foo"</h1>
+            <p data-source-start="7" data-source-end="17">"Bar " <em>"baz"</em></p>
+            <hr data-source-start="18" data-source-end="22" />
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn nested_blocks() {
+    let source = "> - foo\n>\n>   bar\n";
+    assert_eq!(
+        to_html_string(source, Options::empty(), CMarkStyle::default(), config()),
+        concat!(
+            "<div><blockquote data-source-start=\"0\" data-source-end=\"18\">",
+            "<ul data-source-start=\"2\" data-source-end=\"18\">",
+            "<li data-source-start=\"2\" data-source-end=\"18\">",
+            "<p data-source-start=\"4\" data-source-end=\"8\">foo</p>",
+            "<p data-source-start=\"14\" data-source-end=\"18\">bar</p>",
+            "</li></ul></blockquote></div>",
+        )
+    );
+    assert_eq!(source_block_at(source, Options::empty(), 4), Some(4..8));
+    assert_eq!(source_block_at(source, Options::empty(), 15), Some(14..18));
+    assert_eq!(source_block_at(source, Options::empty(), 9), Some(2..18));
+}
+
+#[test]
+fn markdown_component() {
+    let source = Value::new("# Foo\n\nBar *baz*\n\n---\n".to_string());
+    let text = "# Foo bar\n\nBar *baz*\n\n---\n";
+
+    log_start();
+    let _el1 = Markdown {
+        config: config(),
+        ..Markdown::new(source.to_computed())
+    }
+    .mount();
+    // Blocks after the changed one are moved
+    source.set(text.to_string());
+    let el1_str = strip_markers(DomDebugFragment::from_log().to_pseudo_html());
+
+    log_start();
+    let _el2 = to_vertigo_with_config(
+        text,
+        Options::ENABLE_TABLES,
+        CMarkStyle::default(),
+        config(),
+    );
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn markdown_stream() {
+    let text = "# Foo\n\n> Bar *baz*\n\n- a\n- b\n";
+    let stream = MarkdownStream::new(Options::empty());
+
+    log_start();
+    let _el1 = stream.render_with_config(CMarkStyle::default(), config());
+    for chunk in text.as_bytes().chunks(5) {
+        stream.push(std::str::from_utf8(chunk).unwrap());
+    }
+    stream.finish();
+    let el1_str = strip_markers(DomDebugFragment::from_log().to_pseudo_html());
+
+    log_start();
+    let _el2 = to_vertigo_with_config(text, Options::empty(), CMarkStyle::default(), config());
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
@@ -0,0 +1,75 @@
+use vertigo::inspect::{log_start, DomDebugFragment};
+
+use super::reactive::strip_markers;
+
+use crate::{
+    stream::complete_tail, to_vertigo_with_config, CMarkConfig, CMarkStyle, MarkdownStream, Options,
+};
+
+fn config() -> CMarkConfig {
+    CMarkConfig {
+        // Theme colors of `<pre>` would be rendered as autocss class in one of the trees
+        #[cfg(feature = "syntect")]
+        highlight_mode: crate::HighlightMode::Classes(crate::ClassStyle::Spaced),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn streamed_chunks() {
+    let text = "# Answer\n\nSome **bold** text.\n\n```rust\nfn main() {}\n```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n> Quote";
+    let opts = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let stream = MarkdownStream::new(opts);
+
+    log_start();
+    let _el1 = stream.render_with_config(CMarkStyle::default(), config());
+    for chunk in text.as_bytes().chunks(7) {
+        stream.push(std::str::from_utf8(chunk).unwrap());
+    }
+    stream.finish();
+    let el1_str = strip_markers(DomDebugFragment::from_log().to_pseudo_html());
+
+    log_start();
+    let _el2 = to_vertigo_with_config(text, opts, CMarkStyle::default(), config());
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+    assert_eq!(stream.text(), text);
+}
+
+#[test]
+fn unclosed_inline() {
+    let opts = Options::ENABLE_STRIKETHROUGH;
+    assert_eq!(complete_tail("Hello **wor", opts), "Hello **wor**");
+    assert_eq!(complete_tail("Hello **", opts), "Hello ");
+    assert_eq!(complete_tail("*a ~~b `c", opts), "*a ~~b `c`~~*");
+    assert_eq!(
+        complete_tail("snake_case and _it", opts),
+        "snake_case and _it_"
+    );
+    assert_eq!(complete_tail("* item **a** *b", opts), "* item **a** *b*");
+    assert_eq!(complete_tail("Done.\n\nNext *", opts), "Done.\n\nNext ");
+}
+
+#[test]
+fn unclosed_fence() {
+    let opts = Options::empty();
+    assert_eq!(
+        complete_tail("```rust\nlet a = *b;\n``", opts),
+        "```rust\nlet a = *b;\n"
+    );
+    assert_eq!(complete_tail("~~~\ncode *", opts), "~~~\ncode *");
+}
+
+#[test]
+fn table_header_only() {
+    let opts = Options::ENABLE_TABLES;
+    assert_eq!(
+        complete_tail("| a | b |\n| -", opts),
+        "| a | b |\n|---|---|"
+    );
+    assert_eq!(
+        complete_tail("a | b | c\n--", opts),
+        "a | b | c\n|---|---|---|"
+    );
+}
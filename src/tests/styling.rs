@@ -35,6 +35,33 @@ fn blockquote() {
     assert_eq!(el1_str, el2_str);
 }
 
+#[test]
+fn alerts() {
+    const NOTE_STYLE: &str = "color: blue";
+
+    log_start();
+    let _el1 = to_vertigo_opts_styled(
+        "> [!NOTE]\n> Lorem\n\n> [!TIP]\n> Ipsum",
+        Options::ENABLE_GFM,
+        crate::CMarkStyle {
+            alert_note: Css::str(NOTE_STYLE),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <blockquote class="markdown-alert-note" style={NOTE_STYLE}><p>"Lorem"</p></blockquote>
+            <blockquote class="markdown-alert-tip"><p>"Ipsum"</p></blockquote>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
 #[cfg(not(feature = "syntect"))]
 #[test]
 fn codeblock() {
@@ -73,7 +100,15 @@ dolor sit amet
     let el1_str = DomDebugFragment::from_log().to_pseudo_html();
 
     log_start();
-    let _el2 = dom! { <div><pre style={TEST_STYLE}><span style="color: rgba(211, 208, 200, 255); background_color: rgba(45, 45, 45, 255)">"dolor sit amet\n"</span></pre></div> };
+    let _el2 = dom! {
+        <div>
+            <pre style="background-color: rgba(45, 45, 45, 1); color: rgba(211, 208, 200, 1); color: green">
+                <code>
+                    <span style="color: rgba(211, 208, 200, 1)">"dolor sit amet\n"</span>
+                </code>
+            </pre>
+        </div>
+    };
     let el2_str = DomDebugFragment::from_log().to_pseudo_html();
 
     assert_eq!(el1_str, el2_str);
@@ -134,6 +169,53 @@ Coffee
     assert_eq!(el1_str, el2_str);
 }
 
+#[test]
+fn footnotes() {
+    const SECTION_STYLE: &str = "color: gray";
+    const BACKREF_STYLE: &str = "color: red";
+    const LIST_STYLE: &str = "padding-left: 2em";
+    const LABEL_STYLE: &str = "color: blue";
+
+    log_start();
+    let _el1 = to_vertigo_opts_styled(
+        "Lorem[^1]\n\n[^1]: Ipsum",
+        Options::ENABLE_FOOTNOTES,
+        crate::CMarkStyle {
+            footnote_reference: test_css(),
+            footnote_definition: test_css(),
+            footnote_label: Css::str(LABEL_STYLE),
+            footnote_backref: Css::str(BACKREF_STYLE),
+            footnotes: Css::str(SECTION_STYLE),
+            footnotes_list: Css::str(LIST_STYLE),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>
+                "Lorem"
+                <sup class="footnote-reference" style={TEST_STYLE}>
+                    <a href="#fn-1" id="fnref-1" style={LABEL_STYLE}>"1"</a>
+                </sup>
+            </p>
+            <section class="footnotes" style={SECTION_STYLE}>
+                <ol style={LIST_STYLE}>
+                    <li id="fn-1" style={TEST_STYLE}>
+                        <p>"Ipsum"</p>
+                        <a href="#fnref-1" class="footnote-backref" style={BACKREF_STYLE}>"↩"</a>
+                    </li>
+                </ol>
+            </section>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
 #[test]
 fn headers() {
     const H1_STYLE: &str = "color: red";
@@ -186,6 +268,34 @@ fn headers() {
     assert_eq!(el1_str, el2_str);
 }
 
+#[test]
+fn inline_code_and_breaks() {
+    const HR_STYLE: &str = "color: gray";
+
+    log_start();
+    let _el1 = to_vertigo_styled(
+        "Lorem `ipsum`  \ndolor\n\n---",
+        crate::CMarkStyle {
+            code: test_css(),
+            br: test_css(),
+            hr: Css::str(HR_STYLE),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <p>"Lorem "<code style={TEST_STYLE}>"ipsum"</code><br style={TEST_STYLE} />"dolor"</p>
+            <hr style={HR_STYLE} />
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
 #[test]
 fn image() {
     log_start();
@@ -263,6 +373,102 @@ fn lists() {
     assert_eq!(el1_str, el2_str);
 }
 
+#[test]
+fn math() {
+    log_start();
+    let _el1 = to_vertigo_opts_styled(
+        "Lorem $x^2$",
+        Options::ENABLE_MATH,
+        crate::CMarkStyle {
+            math_inline: test_css(),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div><p>"Lorem "<span class="math math-inline" style={TEST_STYLE}>"x^2"</span></p></div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn nested_blockquotes() {
+    const ODD_STYLE: &str = "background-color: white";
+    const EVEN_STYLE: &str = "background-color: gray";
+
+    log_start();
+    let _el1 = to_vertigo_styled(
+        "> One\n>> Two\n>>> Three",
+        crate::CMarkStyle {
+            blockquote_depths: vec![Css::str(ODD_STYLE), Css::str(EVEN_STYLE)],
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <blockquote style={ODD_STYLE}>
+                <p>"One"</p>
+                <blockquote style={EVEN_STYLE}>
+                    <p>"Two"</p>
+                    <blockquote style={ODD_STYLE}>
+                        <p>"Three"</p>
+                    </blockquote>
+                </blockquote>
+            </blockquote>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn nested_lists() {
+    const TOP_STYLE: &str = "list-style-type: disc";
+    const NESTED_STYLE: &str = "list-style-type: circle";
+    const ITEM_STYLE: &str = "margin-left: 1em";
+
+    log_start();
+    let _el1 = to_vertigo_styled(
+        "- One\n  - Two\n    1. Three",
+        crate::CMarkStyle {
+            ul_depths: vec![Css::str(TOP_STYLE), Css::str(NESTED_STYLE)],
+            li_depths: vec![Css::default(), Css::default(), Css::str(ITEM_STYLE)],
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <ul style={TOP_STYLE}>
+                <li>
+                    "One"
+                    <ul style={NESTED_STYLE}>
+                        <li>
+                            "Two"
+                            <ol>
+                                <li style={ITEM_STYLE}>"Three"</li>
+                            </ol>
+                        </li>
+                    </ul>
+                </li>
+            </ul>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
 #[test]
 fn paragraph() {
     log_start();
@@ -302,7 +508,7 @@ Lorem **ipsum** *dolor* sit[^1] ~amet~ ~~plumeth~~
             em: Css::str(EM_STYLE),
             strong: Css::str(STRONG_STYLE),
             sub: Css::str(SUB_STYLE),
-            sup: Css::str(SUP_STYLE),
+            footnote_reference: Css::str(SUP_STYLE),
             del: Css::str(DEL_STYLE),
             ..Default::default()
         },
@@ -316,11 +522,15 @@ Lorem **ipsum** *dolor* sit[^1] ~amet~ ~~plumeth~~
                 "Lorem "
                 <strong style={STRONG_STYLE}>"ipsum"</strong>" "
                 <em style={EM_STYLE}>"dolor"</em>" sit"
-                <sup class="footnote-reference autocss_3"><a href="#1">"1"</a></sup>" "
+                <sup class="footnote-reference autocss_3"><a href="#fn-1" id="fnref-1">"1"</a></sup>" "
                 <sub style={SUB_STYLE}>"amet"</sub>" "
                 <del style={DEL_STYLE}>"plumeth"</del>
             </p>
-            <div class="footnote-definition" id="1"><sup class="footnote-definition-label autocss_4">"1"</sup><p>"Legend"</p></div>
+            <section class="footnotes">
+                <ol>
+                    <li id="fn-1"><p>"Legend"</p><a class="footnote-backref" href="#fnref-1">"↩"</a></li>
+                </ol>
+            </section>
         </div>
     };
     let el2_str = DomDebugFragment::from_log().to_pseudo_html();
@@ -378,3 +588,53 @@ fn table() {
 
     assert_eq!(el1_str, el2_str);
 }
+
+#[test]
+fn task_list() {
+    log_start();
+    let _el1 = to_vertigo_opts_styled(
+        "- [x] Lorem",
+        Options::ENABLE_TASKLISTS,
+        crate::CMarkStyle {
+            input: test_css(),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <ul>
+                <li><input checked="checked" disabled="" type="checkbox" style={TEST_STYLE} />"Lorem"</li>
+            </ul>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
+
+#[test]
+fn presets() {
+    for style in [
+        crate::CMarkStyle::github(),
+        crate::CMarkStyle::github_dark(),
+        crate::CMarkStyle::minimal(),
+    ] {
+        assert!(!style.container.groups.is_empty());
+        assert!(!style.table.groups.is_empty());
+        assert!(!style.codeblock.groups.is_empty());
+        assert!(!style.alert_warning.groups.is_empty());
+    }
+
+    log_start();
+    let _el1 = to_vertigo_styled("Lorem", crate::CMarkStyle::github());
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = to_vertigo_styled("Lorem", crate::CMarkStyle::github_dark());
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_ne!(el1_str, el2_str);
+}
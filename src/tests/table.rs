@@ -19,7 +19,7 @@ fn table_1() {
     log_start();
     let _el2 = dom! {
         <div>
-            <table style="border: 1">
+            <table>
                 <thead>
                     <tr>
                         <th>"foo"</th>
@@ -57,7 +57,7 @@ fn table_2() {
     log_start();
     let _el2 = dom! {
         <div>
-            <table style="border: 1">
+            <table>
                 <thead>
                     <tr>
                         <th>"Head cell"</th>
@@ -106,7 +106,7 @@ I'm saying something
         <div>
             <h1>"Something"</h1>
             <p>"I'm saying something"</p>
-            <table style="border: 1">
+            <table>
                 <thead>
                     <tr>
                         <th>"Head 1"</th>
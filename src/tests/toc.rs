@@ -0,0 +1,157 @@
+use vertigo::{
+    dom,
+    inspect::{log_start, DomDebugFragment},
+    Value,
+};
+
+use super::reactive::strip_markers;
+use crate::{
+    to_vertigo_with_config, toc, CMarkConfig, CMarkStyle, HeadingLevel, Markdown, Options,
+    TocConfig, TocEntry,
+};
+
+const DOCUMENT: &str = r#"
+# Guide
+
+[TOC]
+
+## Install
+
+### From source
+
+## Usage {#use}
+
+#### Deep
+
+# Appendix
+"#;
+
+fn entry(level: HeadingLevel, text: &str, id: &str, children: Vec<TocEntry>) -> TocEntry {
+    TocEntry {
+        level,
+        text: text.to_string(),
+        id: id.to_string(),
+        children,
+    }
+}
+
+#[test]
+fn outline() {
+    let entries = toc(
+        DOCUMENT,
+        Options::ENABLE_HEADING_ATTRIBUTES,
+        &TocConfig {
+            min_level: HeadingLevel::H2,
+            max_level: HeadingLevel::H3,
+        },
+    );
+
+    assert_eq!(
+        entries,
+        vec![
+            entry(
+                HeadingLevel::H2,
+                "Install",
+                "install",
+                vec![entry(
+                    HeadingLevel::H3,
+                    "From source",
+                    "from-source",
+                    vec![]
+                )],
+            ),
+            entry(HeadingLevel::H2, "Usage", "use", vec![]),
+        ]
+    );
+}
+
+#[test]
+fn placeholder() {
+    let config = CMarkConfig {
+        toc: Some(TocConfig {
+            max_level: HeadingLevel::H2,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        DOCUMENT,
+        Options::ENABLE_HEADING_ATTRIBUTES,
+        CMarkStyle::default(),
+        config.clone(),
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <h1 id="guide">"Guide"</h1>
+            <ul class="toc">
+                <li>
+                    <a href="#guide">"Guide"</a>
+                    <ul>
+                        <li><a href="#install">"Install"</a></li>
+                        <li><a href="#use">"Usage"</a></li>
+                    </ul>
+                </li>
+                <li><a href="#appendix">"Appendix"</a></li>
+            </ul>
+            <h2 id="install">"Install"</h2>
+            <h3 id="from-source">"From source"</h3>
+            <h2 id="use">"Usage"</h2>
+            <h4 id="deep">"Deep"</h4>
+            <h1 id="appendix">"Appendix"</h1>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+
+    log_start();
+    let _el3 = Markdown {
+        opts: Options::ENABLE_HEADING_ATTRIBUTES,
+        config,
+        ..Markdown::new(Value::new(DOCUMENT.to_string()).to_computed())
+    }
+    .mount();
+    let el3_str = strip_markers(DomDebugFragment::from_log().to_pseudo_html());
+
+    assert_eq!(el3_str, el2_str);
+}
+
+#[test]
+fn placeholder_styled() {
+    const TOC_STYLE: &str = "padding-left: 0";
+    const LI_STYLE: &str = "color: green";
+
+    log_start();
+    let _el1 = to_vertigo_with_config(
+        "[TOC]\n\n# Title",
+        Options::empty(),
+        CMarkStyle {
+            toc: vertigo::Css::str(TOC_STYLE),
+            li: vertigo::Css::str(LI_STYLE),
+            ..Default::default()
+        },
+        CMarkConfig {
+            toc: Some(TocConfig::default()),
+            ..Default::default()
+        },
+    );
+    let el1_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    log_start();
+    let _el2 = dom! {
+        <div>
+            <ul class="toc" style={TOC_STYLE}>
+                <li style={LI_STYLE}><a href="#title">"Title"</a></li>
+            </ul>
+            <h1 id="title">"Title"</h1>
+        </div>
+    };
+    let el2_str = DomDebugFragment::from_log().to_pseudo_html();
+
+    assert_eq!(el1_str, el2_str);
+}
@@ -0,0 +1,202 @@
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use vertigo::DomNode;
+
+use crate::{
+    slug::Slugs,
+    styling::{join_classes, styled},
+    tree::Element,
+    CMarkClasses, CMarkStyle, Options,
+};
+
+const TOC_PLACEHOLDER: &str = "[TOC]";
+
+/// Settings of table of contents rendered in place of `[TOC]` paragraph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TocConfig {
+    /// Highest level of headings included
+    pub min_level: HeadingLevel,
+    /// Lowest level of headings included
+    pub max_level: HeadingLevel,
+}
+
+impl Default for TocConfig {
+    fn default() -> Self {
+        Self {
+            min_level: HeadingLevel::H1,
+            max_level: HeadingLevel::H6,
+        }
+    }
+}
+
+/// Heading of the document with headings nested in its section.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TocEntry {
+    pub level: HeadingLevel,
+    /// Text content of the heading
+    pub text: String,
+    /// Id of the heading, explicit or generated the same way as with [heading_ids](crate::CMarkConfig::heading_ids)
+    pub id: String,
+    pub children: Vec<TocEntry>,
+}
+
+/// Returns outline of the document.
+///
+/// ```rust
+/// use vertigo_cmark::{toc, toc_to_vertigo, Options, TocConfig};
+///
+/// let entries = toc("# Title\n\n## Usage", Options::empty(), &TocConfig::default());
+/// assert_eq!(entries[0].children[0].id, "usage");
+///
+/// let nav = toc_to_vertigo(&entries);
+/// ```
+pub fn toc(text: &str, opts: Options, config: &TocConfig) -> Vec<TocEntry> {
+    let mut collector = Headings::default();
+    let headings = Parser::new_ext(text, opts)
+        .filter_map(|event| collector.event(&event))
+        .collect::<Vec<_>>();
+    build_toc(&headings, config)
+}
+
+/// Renders table of contents as nested lists of links (`ul.toc`).
+pub fn toc_to_vertigo(entries: &[TocEntry]) -> DomNode {
+    toc_list(entries, &CMarkStyle::default(), &CMarkClasses::default())
+        .into_dom()
+        .into()
+}
+
+/// Table of contents as `ul.toc` element, styled as lists of the document.
+pub(crate) fn toc_list(
+    entries: &[TocEntry],
+    styling: &CMarkStyle,
+    classes: &CMarkClasses,
+) -> Element {
+    let list = nested_list(entries, styling, classes);
+    styled(
+        list,
+        &styling.ul.clone().extend(styling.toc.clone()),
+        &join_classes(["toc", &classes.ul, &classes.toc]),
+    )
+}
+
+fn nested_list(entries: &[TocEntry], styling: &CMarkStyle, classes: &CMarkClasses) -> Element {
+    let mut list = Element::new("ul");
+    for entry in entries {
+        let link = Element::new("a")
+            .attr("href", format!("#{}", entry.id))
+            .child_text(&entry.text);
+        let mut item = styled(
+            Element::new("li").child(styled(link, &styling.a, &classes.a)),
+            &styling.li,
+            &classes.li,
+        );
+        if !entry.children.is_empty() {
+            let children = nested_list(&entry.children, styling, classes);
+            item.add_child(styled(children, &styling.ul, &classes.ul));
+        }
+        list.add_child(item);
+    }
+    list
+}
+
+/// Heading found in the document.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Heading {
+    pub level: HeadingLevel,
+    pub text: String,
+    pub id: String,
+    /// Whether the id was generated from text
+    pub generated: bool,
+}
+
+/// Collects headings from events, generating unique ids the same way as the writer.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Headings {
+    slugs: Slugs,
+    current: Option<(HeadingLevel, Option<String>, String)>,
+}
+
+impl Headings {
+    /// Processes an event, returns heading when it ends.
+    pub fn event(&mut self, event: &Event) -> Option<Heading> {
+        match event {
+            Event::Start(Tag::Heading { level, id, .. }) => {
+                if let Some(id) = id {
+                    self.slugs.register(id);
+                }
+                self.current = Some((*level, id.as_ref().map(|id| id.to_string()), String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, heading)) = &mut self.current {
+                    heading.push_str(text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let (level, id, text) = self.current.take()?;
+                let generated = id.is_none();
+                let id = id.unwrap_or_else(|| self.slugs.unique(&text));
+                return Some(Heading {
+                    level,
+                    text,
+                    id,
+                    generated,
+                });
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Nests headings within provided levels into sections.
+pub(crate) fn build_toc(headings: &[Heading], config: &TocConfig) -> Vec<TocEntry> {
+    let mut entries: Vec<TocEntry> = vec![];
+    for heading in headings {
+        if heading.level < config.min_level || heading.level > config.max_level {
+            continue;
+        }
+        let entry = TocEntry {
+            level: heading.level,
+            text: heading.text.clone(),
+            id: heading.id.clone(),
+            children: vec![],
+        };
+        // Descend through last entries of higher level
+        let mut siblings = &mut entries;
+        while siblings
+            .last()
+            .map(|last| last.level < heading.level)
+            .unwrap_or(false)
+        {
+            siblings = &mut siblings.last_mut().unwrap().children;
+        }
+        siblings.push(entry);
+    }
+    entries
+}
+
+/// Whether paragraph with this text should be replaced with table of contents
+pub(crate) fn is_toc_placeholder(text: &str) -> bool {
+    text.trim() == TOC_PLACEHOLDER
+}
+
+/// Whether events contain a paragraph with `[TOC]` placeholder
+pub(crate) fn has_toc_placeholder(events: &[Event]) -> bool {
+    let mut paragraph: Option<String> = None;
+    for event in events {
+        match event {
+            Event::Start(Tag::Paragraph) => paragraph = Some(String::new()),
+            Event::Text(text) => {
+                if let Some(paragraph) = &mut paragraph {
+                    paragraph.push_str(text);
+                }
+            }
+            Event::End(TagEnd::Paragraph) => {
+                if paragraph.take().map(|text| is_toc_placeholder(&text)) == Some(true) {
+                    return true;
+                }
+            }
+            _ => paragraph = None,
+        }
+    }
+    false
+}
@@ -0,0 +1,210 @@
+use std::rc::Rc;
+use vertigo::{Css, CssGroup, DomElement, DomNode, DomText};
+
+use crate::html::is_void_element;
+
+/// Element created by the writer, converted to vertigo element or HTML string when complete.
+pub(crate) struct Element {
+    name: &'static str,
+    attrs: Vec<(&'static str, String)>,
+    css: Css,
+    children: Vec<Node>,
+    /// Element provided by [render hooks](crate::RenderHooks) used instead of the named one
+    hooked: Option<DomElement>,
+    /// Click handler, used only in vertigo tree
+    on_click: Option<Rc<dyn Fn()>>,
+}
+
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+    /// Node rendered by the app (custom code block renderer)
+    Dom(DomNode),
+}
+
+impl Element {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            attrs: vec![],
+            css: Css::default(),
+            children: vec![],
+            hooked: None,
+            on_click: None,
+        }
+    }
+
+    /// Wraps element from render hooks, attributes and children are added to it.
+    pub fn hooked(element: DomElement) -> Self {
+        Self {
+            hooked: Some(element),
+            ..Self::new("")
+        }
+    }
+
+    pub fn attr(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.add_attr(name, value);
+        self
+    }
+
+    pub fn add_attr(&mut self, name: &'static str, value: impl ToString) {
+        self.attrs.push((name, value.to_string()));
+    }
+
+    pub fn css(mut self, css: &Css) -> Self {
+        self.css = std::mem::take(&mut self.css).extend(css.clone());
+        self
+    }
+
+    pub fn on_click(mut self, on_click: impl Fn() + 'static) -> Self {
+        self.on_click = Some(Rc::new(on_click));
+        self
+    }
+
+    pub fn child(mut self, child: impl Into<Node>) -> Self {
+        self.add_child(child);
+        self
+    }
+
+    pub fn child_text(self, text: impl ToString) -> Self {
+        self.child(Node::text(text))
+    }
+
+    pub fn add_child(&mut self, child: impl Into<Node>) {
+        self.children.push(child.into());
+    }
+
+    pub fn into_dom(self) -> DomElement {
+        let name = self.name;
+        let mut element = self.hooked.unwrap_or_else(|| DomElement::new(name));
+        for (name, value) in self.attrs {
+            element.add_attr(name, value);
+        }
+        if !self.css.groups.is_empty() {
+            element = element.css(self.css);
+        }
+        if let Some(on_click) = self.on_click {
+            element = element.on_click(move |_| on_click());
+        }
+        for child in self.children {
+            element.add_child(child.into_dom());
+        }
+        element
+    }
+
+    fn write_html(&self, out: &mut String) {
+        // Elements from hooks can't be serialized, only their content is written
+        let name = if self.hooked.is_some() { "" } else { self.name };
+        if !name.is_empty() {
+            out.push('<');
+            out.push_str(name);
+            let mut style = self
+                .attrs
+                .iter()
+                .filter(|(attr, _)| *attr == "style")
+                .map(|(_, value)| value.trim().trim_end_matches(';').to_string())
+                .collect::<Vec<_>>();
+            style.extend(css_declarations(&self.css));
+            for (attr, value) in self.attrs.iter().filter(|(attr, _)| *attr != "style") {
+                write_attr(out, attr, value);
+            }
+            if !style.is_empty() {
+                write_attr(out, "style", &style.join("; "));
+            }
+            out.push('>');
+            if is_void_element(name) {
+                return;
+            }
+        }
+        for child in &self.children {
+            child.write_html(out);
+        }
+        if !name.is_empty() {
+            out.push_str("</");
+            out.push_str(name);
+            out.push('>');
+        }
+    }
+}
+
+impl Node {
+    pub fn text(text: impl ToString) -> Self {
+        Self::Text(text.to_string())
+    }
+
+    pub fn into_dom(self) -> DomNode {
+        match self {
+            Self::Element(element) => element.into_dom().into(),
+            Self::Text(text) => DomText::new(text).into(),
+            Self::Dom(node) => node,
+        }
+    }
+
+    /// Calls click handlers of the node and its descendants in document order.
+    #[cfg(test)]
+    pub fn click_all(&self) {
+        if let Self::Element(element) = self {
+            if let Some(on_click) = &element.on_click {
+                on_click();
+            }
+            for child in &element.children {
+                child.click_all();
+            }
+        }
+    }
+
+    /// Writes the node as HTML, nodes rendered by the app are skipped.
+    pub fn write_html(&self, out: &mut String) {
+        match self {
+            Self::Element(element) => element.write_html(out),
+            Self::Text(text) => escape(out, text, false),
+            Self::Dom(_) => {}
+        }
+    }
+}
+
+impl From<Element> for Node {
+    fn from(element: Element) -> Self {
+        Self::Element(element)
+    }
+}
+
+impl From<DomNode> for Node {
+    fn from(node: DomNode) -> Self {
+        Self::Dom(node)
+    }
+}
+
+/// Css declarations to be used in `style` attribute, media queries are skipped.
+fn css_declarations(css: &Css) -> Vec<String> {
+    css.groups
+        .iter()
+        .filter_map(|group| match group {
+            CssGroup::CssStatic { value } => Some(value.to_string()),
+            CssGroup::CssDynamic { value } => Some(value.clone()),
+            CssGroup::CssMedia { .. } => None,
+        })
+        .map(|value| value.trim().trim_end_matches(';').to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+fn write_attr(out: &mut String, name: &str, value: &str) {
+    out.push(' ');
+    out.push_str(name);
+    out.push_str("=\"");
+    escape(out, value, true);
+    out.push('"');
+}
+
+fn escape(out: &mut String, text: &str, attr: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attr => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}